@@ -1,21 +1,295 @@
+mod options;
+mod serde;
+
 use itertools::multiunzip;
 use proc_macro::TokenStream;
 use proc_macro2::Span;
 use proc_macro_crate::{crate_name, FoundCrate};
 use proc_macro_error::{abort, abort_if_dirty, emit_error, proc_macro_error};
-use quote::quote;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
+
+use crate::options::{InlineMode, Options, SerdeMode};
 use syn::{
-    parse_macro_input, Data, DeriveInput, GenericParam, Ident, LifetimeParam, Member, Token, Type,
-    TypeParam, TypeTuple,
+    meta, parse_macro_input, parse_quote, Attribute, ConstParam, Data, DeriveInput, Expr, ExprPath,
+    GenericArgument, GenericParam, Ident, Path, Token, Type, TypeParam, TypePath,
 };
 
+/// Names of the inherent items generated on every array-struct, and of the
+/// `ArrayStruct` and `ArrayStructExt` items, which per-field accessors must not
+/// collide with.
+const GENERATED_METHODS: &[&str] = &[
+    "from_val",
+    "val",
+    "refs",
+    "muts",
+    "to_array",
+    "from_array",
+    "reinterpret",
+    "FIELD_COUNT",
+    "iter",
+    "iter_mut",
+    "fold",
+    "contains",
+    "sum",
+    "product",
+    "new",
+    "from_fn",
+    "from_array_into",
+    "try_from_iter",
+    "splat",
+    "len",
+    "is_empty",
+    "to_array_ref",
+    "copy_array",
+    "clone_array",
+    "as_slice",
+    "as_mut_slice",
+    "chunks",
+    "windows",
+    "split_first",
+    "split_first_mut",
+    "split_last",
+    "split_last_mut",
+    "each_ref",
+    "each_mut",
+    "into_values",
+    "indexed_refs",
+    "map_in_place",
+    "all",
+    "any",
+    "reduce",
+    "min_element",
+    "max_element",
+    "min_element_by",
+    "max_element_by",
+    "map_array",
+    "map_array_with_index",
+    "map_fields",
+    "try_map_array",
+    "try_map",
+    "zip",
+    "zip_values",
+    "swap",
+    "reverse",
+    "reversed",
+    "fill",
+    "rotated_left",
+    "rotated_right",
+    "concat_array",
+    "split_array",
+    "to_matrix",
+    "project",
+    "get",
+    "get_mut",
+    "NAMES",
+    "FIELD_OFFSETS",
+    "name_of",
+    "index_of",
+    "get_by_name",
+    "get_by_name_mut",
+    "named_refs",
+    "named_muts",
+    "enumerate_named_mut",
+    "find_name",
+    "to_map",
+];
+
+/// Emits an error for every field whose accessors would collide with another
+/// generated method. Each accessor is named by a `(prefix, suffix)` pair around
+/// the field name.
+fn check_method_collisions(ident_fields: &[Ident], methods: &[&str], affixes: &[(&str, &str)]) {
+    let accessors = |ident: &Ident| {
+        let name = ident.unraw().to_string();
+        affixes
+            .iter()
+            .map(|(prefix, suffix)| format!("{prefix}{name}{suffix}"))
+            .collect::<Vec<_>>()
+    };
+    for (i, ident) in ident_fields.iter().enumerate() {
+        for accessor in accessors(ident) {
+            let collides_with_field = ident_fields
+                .iter()
+                .enumerate()
+                .any(|(j, other)| i != j && accessors(other).contains(&accessor));
+            if methods.contains(&accessor.as_str()) || collides_with_field {
+                emit_error!(
+                    ident,
+                    "the accessor `{}` for this field collides with another generated method",
+                    accessor;
+                    help = "consider renaming the field"
+                );
+            }
+        }
+    }
+}
+
+/// Checks any `#[repr(...)]` attributes written on the struct, returning
+/// `None` if there weren't any. Otherwise they're only inspected when the
+/// `bytemuck` option is set, allowing just `C` and `transparent` since anything
+/// else could add padding, and the result is whether they include
+/// `transparent`. Without `bytemuck` they're kept unchecked, giving
+/// `Some(false)`.
+fn check_user_repr(attrs: &[Attribute], bytemuck: bool) -> Option<bool> {
+    let mut found = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        let transparent = found.get_or_insert(false);
+        if !bytemuck {
+            continue;
+        }
+        let result = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("transparent") {
+                *transparent = true;
+                Ok(())
+            } else if meta.path.is_ident("C") {
+                Ok(())
+            } else {
+                Err(meta.error("only `repr(C)` and `repr(transparent)` are supported with the `bytemuck` option"))
+            }
+        });
+        if let Err(err) = result {
+            emit_error!(err.span(), "{}", err);
+        }
+    }
+    found
+}
+
+/// Emits an error if the field type is obviously unsized, which would
+/// otherwise fail deep inside the generated code since it can't be an array
+/// element.
+fn check_sized(field_ty: &Type) {
+    let unsized_kind = match field_ty {
+        Type::Path(TypePath { qself: None, path }) if path.is_ident("str") => "`str` is",
+        Type::Slice(_) => "slices are",
+        Type::TraitObject(_) => "trait objects are",
+        _ => return,
+    };
+    emit_error!(
+        field_ty,
+        "{} unsized, so can't be the field type of an array-struct", unsized_kind;
+        help = "consider a reference or a `Box` instead"
+    );
+}
+
+/// Removes the named traits from any `#[derive(...)]` attributes, for traits
+/// which the macro implements itself.
+fn strip_derives(attrs: &[Attribute], names: &[&str]) -> Vec<Attribute> {
+    attrs
+        .iter()
+        .filter_map(|attr| {
+            if !attr.path().is_ident("derive") {
+                return Some(attr.clone());
+            }
+            let paths = match attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+            {
+                Ok(paths) => paths,
+                Err(_) => return Some(attr.clone()),
+            };
+            let paths: Punctuated<Path, Token![,]> = paths
+                .into_iter()
+                .filter(|path| {
+                    let last = path.segments.last().map(|segment| &segment.ident);
+                    !last.is_some_and(|ident| names.iter().any(|name| ident == name))
+                })
+                .collect();
+            (!paths.is_empty()).then(|| parse_quote!(#[derive(#paths)]))
+        })
+        .collect()
+}
+
 /// A derive-like macro which replaces a field-struct declaration with a
 /// tuple-struct declaration containing a single array. All fields in the
 /// original declaration must share the same type.
 ///
+/// An enum named after the struct with a `Field` suffix, e.g. `FooField`, is
+/// also generated, with a variant for each field.
+///
 /// This attribute should almost always come before to any `derive` macros.
+///
+/// The field type may need dropping, e.g. `String`. Because of that, the
+/// conversions which move every field, `from_val` and `val`, can't be `const`
+/// (the compiler can't yet tell that no field is left behind to drop). `new`,
+/// the tuple constructor, `refs`, `muts`, and the field accessors are `const`
+/// instead.
+///
+/// The attribute accepts the following options, e.g. `#[array_as_struct(ops)]`:
+///
+/// * `ops`: implement `Add`, `Sub`, `Mul`, and `Div` elementwise between two
+///   instances of the struct, and between the struct and a single field value
+///   (along with the corresponding `*Assign` traits for the latter), `Neg`
+///   elementwise, and the `dot`, `length_squared`, `clamp`, and
+///   `clamp_scalar` methods. For field types implementing `Float`, `length`
+///   and `normalize` work too, which for `f32` and `f64` needs the `std` or
+///   `libm` feature of array-as-struct. It's an error on a struct without
+///   fields
+/// * `bitops`: implement `BitAnd`, `BitOr`, and `BitXor` elementwise between
+///   two instances of the struct, along with the corresponding `*Assign`
+///   traits, `Not` elementwise, and `Shl` and `Shr` (along with `ShlAssign`
+///   and `ShrAssign`) shifting every field by the same amount, of any type the
+///   field type can be shifted by. Unlike `ops`, this suits integer and flag
+///   fields rather than floats
+/// * `debug`: implement `Debug` by printing the fields by name, like a derive on
+///   the original declaration would. This replaces any `Debug` derive
+/// * `display`: implement `Display` by printing `name=value` for each field,
+///   separated by commas, or by newlines with the alternate flag (`{:#}`)
+/// * `hash`: implement `Hash` exactly like the underlying array, so hashes
+///   agree with the `Borrow` impl for the array. This replaces any `Hash`
+///   derive
+/// * `ord`: implement `PartialOrd` and `Ord` by comparing the fields
+///   lexicographically in declaration order, like the underlying array. This
+///   replaces any `PartialOrd` or `Ord` derive
+/// * `with`: generate a `with_<field>` method for each field, which replaces
+///   that field and returns the modified struct
+/// * `swizzle`: generate a `swizzle` method taking a const index for each
+///   field, which copies the struct with its fields reordered
+/// * `serde`: implement `Serialize` and `Deserialize` like a derive on the
+///   original declaration would, i.e. as a map of field names to values. A
+///   field can be given another name with `#[serde(rename = "name")]`, which
+///   is the only `serde` field attribute supported. This requires the `serde`
+///   feature. `serde = "array"` instead implements them like the underlying
+///   array, i.e. as a sequence of values
+/// * `bytemuck`: implement `bytemuck::Zeroable` and `bytemuck::Pod` when the
+///   field type implements them. This requires the `bytemuck` feature, and
+///   since those traits are `unsafe`, it can't be used in crates which
+///   `forbid(unsafe_code)`. When the struct is `#[repr(transparent)]`, also
+///   implement `bytemuck::TransparentWrapper` over the array, and generate
+///   `from_array_ref` and `from_array_mut` to reinterpret references to the
+///   array as references to the struct without copying
+/// * `repr = "C"`: emit `#[repr(C)]` instead of `#[repr(transparent)]`. Either
+///   way, the struct has the same layout as its array. A `#[repr(...)]`
+///   attribute written on the struct is used as is instead
+/// * `align(N)`: over-align the struct to `N` bytes, e.g. for SIMD or GPU
+///   buffers. Since `repr(transparent)` can't be combined with an alignment,
+///   this emits `#[repr(C, align(N))]` instead, so the struct may be larger
+///   than its array, with padding at the end. It can't be combined with
+///   `repr = "transparent"`, a `#[repr(...)]` attribute written on the
+///   struct, or `bytemuck`
+/// * `value = "Name"`, `refs = "Name"`, `muts = "Name"`, `index = "Name"`:
+///   rename the helper types behind the corresponding `ArrayStruct` associated
+///   types, which otherwise keep those names
+/// * `expose`: declare the helper types next to the struct instead of hiding
+///   them, so they can be named directly. By default they're named after the
+///   struct, e.g. `FooValue`, `FooRefs`, `FooMuts`, and `FooIndex`
+/// * `inline = "always"`, `"hint"`, or `"never"`: how the generated methods
+///   are marked for inlining. By default small methods are `#[inline(always)]`
+///   and the rest `#[inline]`. `"hint"` makes them all `#[inline]`, and
+///   `"never"` makes them all `#[inline(never)]`, which can help code size
+/// * `private_array`: leave the array field private, so outside its module the
+///   struct can only be built and taken apart through the generated methods
+///   and conversions, like `new` and `to_array`
+/// * `keep_named = "Name"`: also declare the original named-field struct
+///   under the given name, converting to and from the array-struct with
+///   `From`. This is the `Value` helper type, declared next to the struct
+/// * `value_derives(Trait, ...)`: derive exactly these traits on the `Value`
+///   helper type, instead of copying the struct's derives. The struct's other
+///   attributes are still copied
+/// * `element = Type`: the type of every field, which is required for a struct
+///   without fields and otherwise checked against each field
+///
+/// With the `alloc` feature of array-as-struct, methods which allocate, like
+/// `to_map`, are generated too, along with `TryFrom<Vec<T>>`.
 #[proc_macro_error]
 #[proc_macro_attribute]
 pub fn array_as_struct(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -29,7 +303,11 @@ pub fn array_as_struct_doctest(attr: TokenStream, item: TokenStream) -> TokenStr
     array_as_struct_helper(attr, item, true)
 }
 
-fn array_as_struct_helper(_attr: TokenStream, item: TokenStream, doctest: bool) -> TokenStream {
+fn array_as_struct_helper(attr: TokenStream, item: TokenStream, doctest: bool) -> TokenStream {
+    let mut options = Options::default();
+    let options_parser = meta::parser(|meta| options.parse(meta));
+    parse_macro_input!(attr with options_parser);
+
     let found_crate =
         crate_name("array-as-struct").expect("array-as-struct is present in `Cargo.toml`");
     let found_crate = match found_crate {
@@ -54,130 +332,1085 @@ fn array_as_struct_helper(_attr: TokenStream, item: TokenStream, doctest: bool)
         _ => abort!(ast_span, "only named-field structs are supported"),
     };
 
-    // Converts `<F, const D: usize>` (sans `<` and `>`) to
-    //          `<F, D>` (sans `<` and `>`)
-    let generic_params = generics.params;
-    let generic_params_no_attr: Punctuated<GenericParam, Token![,]> = generic_params
+    // Both lists of generic parameters below always end with a `,` (if
+    // non-empty), so more parameters can be appended to them
+    //
+    // The struct and its helper types keep the parameters as declared, but
+    // defaults aren't allowed anywhere else, so they're stripped
+    let struct_generic_params = generics.params;
+    let mut generic_params: Punctuated<GenericParam, Token![,]> = struct_generic_params
         .iter()
         .map(|gen| match gen {
-            GenericParam::Lifetime(x) => GenericParam::Lifetime(LifetimeParam {
-                attrs: vec![],
-                lifetime: x.lifetime.clone(),
-                colon_token: None,
-                bounds: Punctuated::new(),
+            GenericParam::Lifetime(x) => GenericParam::Lifetime(x.clone()),
+            GenericParam::Type(x) => GenericParam::Type(TypeParam {
+                eq_token: None,
+                default: None,
+                ..x.clone()
             }),
-            GenericParam::Type(x) => GenericParam::Type(x.clone()),
-            GenericParam::Const(x) => GenericParam::Type(TypeParam {
-                ident: x.ident.clone(),
-                attrs: vec![],
-                colon_token: None,
-                bounds: Punctuated::new(),
+            GenericParam::Const(x) => GenericParam::Const(ConstParam {
                 eq_token: None,
                 default: None,
+                ..x.clone()
             }),
         })
         .collect();
+    if !generic_params.empty_or_trailing() {
+        generic_params.push_punct(Default::default());
+    }
+    // Converts `<'a: 'b, F: Copy, const D: usize>` (sans `<` and `>`) to
+    //          `<'a, F, D>` (sans `<` and `>`)
+    let mut generic_params_no_attr: Punctuated<GenericArgument, Token![,]> = generic_params
+        .iter()
+        .map(|gen| match gen {
+            GenericParam::Lifetime(x) => GenericArgument::Lifetime(x.lifetime.clone()),
+            GenericParam::Type(x) => GenericArgument::Type(Type::Path(TypePath {
+                qself: None,
+                path: x.ident.clone().into(),
+            })),
+            GenericParam::Const(x) => GenericArgument::Const(Expr::Path(ExprPath {
+                attrs: vec![],
+                qself: None,
+                path: x.ident.clone().into(),
+            })),
+        })
+        .collect();
+    if !generic_params_no_attr.empty_or_trailing() {
+        generic_params_no_attr.push_punct(Default::default());
+    }
+
+    // Also always ends with a `,` (if non-empty), so more predicates can be
+    // appended to it
+    let mut where_predicates = generics
+        .where_clause
+        .map(|where_clause| where_clause.predicates)
+        .unwrap_or_default();
+    if !where_predicates.empty_or_trailing() {
+        where_predicates.push_punct(Default::default());
+    }
+    let where_clause = quote!(where #where_predicates);
 
-    let mut field_ty = None;
+    // The `element` option, or else the first field, decides the type, and
+    // every differing field is reported against it before aborting
+    let mut expected: Option<(String, Type)> = options
+        .element
+        .map(|ty| ("the `element` option specifies".to_owned(), ty));
     let field_info = data.fields.into_iter().map(|field| {
         let ident = match field.ident {
-            Some(ident) => Member::Named(ident.clone()),
+            Some(ident) => ident,
             None => abort!(ast_span, "only named-field structs are supported"),
         };
-        match field_ty.take() {
-            None => field_ty = Some(field.ty),
-            Some(field_ty) if field_ty != field.ty => {
-                emit_error!(field_ty, "type did not match future fields");
-                abort!(field.ty, "type did not match previous fields");
+        // The array length is counted before any `cfg` is evaluated, so it
+        // would disagree with the helper types
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("cfg"))
+        {
+            emit_error!(
+                attr,
+                "`cfg` attributes aren't supported on the fields of an array-struct";
+                help = "put the `cfg` on the whole struct instead"
+            );
+        }
+        match &expected {
+            None => {
+                let note = format!("the first field `{}` has type", ident);
+                expected = Some((note, field.ty));
             }
-            Some(x) => field_ty = Some(x),
+            Some((note, expected_ty)) if *expected_ty != field.ty => {
+                let ty = &field.ty;
+                emit_error!(
+                    field.ty,
+                    "field `{}` has type `{}`, but all fields must have the same type",
+                    ident, quote!(#ty);
+                    note = expected_ty.span() => "{} `{}`", note, quote!(#expected_ty)
+                );
+            }
+            Some(_) => {}
         }
         (field.attrs, field.vis, ident)
     });
     let (attr_fields, vis_fields, ident_fields): (Vec<_>, Vec<_>, Vec<_>) = multiunzip(field_info);
-    let field_ty = field_ty.unwrap_or(Type::Tuple(TypeTuple {
-        paren_token: Default::default(),
-        elems: Punctuated::new(),
-    }));
+    abort_if_dirty();
+    let field_ty = match expected {
+        Some((_, ty)) => ty,
+        None => abort!(
+            ident,
+            "cannot infer the element type of a struct without fields";
+            help = "specify it with `#[array_as_struct(element = ...)]`"
+        ),
+    };
+    check_sized(&field_ty);
+    if options.ops && ident_fields.is_empty() {
+        emit_error!(
+            ident,
+            "the `ops` option does nothing for a struct without fields";
+            help = "remove the `ops` option"
+        );
+    }
+
+    let (inline, inline_large) = match options.inline.unwrap_or(InlineMode::Always) {
+        InlineMode::Always => (quote!(#[inline(always)]), quote!(#[inline])),
+        InlineMode::Hint => (quote!(#[inline]), quote!(#[inline])),
+        InlineMode::Never => (quote!(#[inline(never)]), quote!(#[inline(never)])),
+    };
+
+    let (repr, transparent) = match (check_user_repr(&attrs, options.bytemuck), &options.repr) {
+        (Some(transparent), Some(repr)) => {
+            emit_error!(repr, "the struct already has a `#[repr(...)]` attribute");
+            (None, transparent)
+        }
+        (Some(transparent), None) => {
+            if let Some(align) = &options.align {
+                emit_error!(
+                    align,
+                    "the struct already has a `#[repr(...)]` attribute";
+                    help = "add `align(...)` to that attribute instead"
+                );
+            }
+            (None, transparent)
+        }
+        (None, Some(repr)) => {
+            let transparent = repr.value() == "transparent";
+            let repr = Ident::new(&repr.value(), repr.span());
+            match &options.align {
+                Some(align) if transparent => {
+                    emit_error!(
+                        align,
+                        "`align` can't be combined with `repr = \"transparent\"`"
+                    );
+                    (Some(quote!(#[repr(#repr)])), transparent)
+                }
+                Some(align) => (Some(quote!(#[repr(#repr, align(#align))])), false),
+                None => (Some(quote!(#[repr(#repr)])), transparent),
+            }
+        }
+        // `transparent` can't be combined with `align`, so use `C` instead
+        (None, None) => match &options.align {
+            Some(align) => (Some(quote!(#[repr(C, align(#align))])), false),
+            None => (Some(quote!(#[repr(transparent)])), true),
+        },
+    };
+    if let (true, Some(align)) = (options.bytemuck, &options.align) {
+        emit_error!(
+            align,
+            "`align` can't be combined with the `bytemuck` option, since it may add padding"
+        );
+    }
+    // Reinterpreting references to the array needs `TransparentWrapper`, which
+    // is only sound for `repr(transparent)`
+    let wrap_array = options.bytemuck && transparent;
+
+    let mut accessor_affixes = vec![("", ""), ("", "_mut"), ("", "_copied"), ("set_", "")];
+    if options.with {
+        accessor_affixes.push(("with_", ""));
+    }
+    let mut methods = GENERATED_METHODS.to_vec();
+    if options.swizzle {
+        methods.push("swizzle");
+    }
+    if (1..=12).contains(&ident_fields.len()) {
+        methods.extend(["from_tuple", "into_tuple"]);
+    }
+    if options.ops {
+        methods.extend([
+            "dot",
+            "length_squared",
+            "length",
+            "normalize",
+            "clamp_scalar",
+            "clamp",
+        ]);
+    }
+    if wrap_array {
+        methods.extend(["from_array_ref", "from_array_mut"]);
+    }
+    check_method_collisions(&ident_fields, &methods, &accessor_affixes);
+    let ident_fields_mut: Vec<_> = ident_fields
+        .iter()
+        .map(|ident| format_ident!("{}_mut", ident))
+        .collect();
+    let ident_fields_copied: Vec<_> = ident_fields
+        .iter()
+        .map(|ident| format_ident!("{}_copied", ident))
+        .collect();
+    let ident_fields_set: Vec<_> = ident_fields
+        .iter()
+        .map(|ident| format_ident!("set_{}", ident))
+        .collect();
+    let ident_fields_with: Vec<_> = ident_fields
+        .iter()
+        .map(|ident| format_ident!("with_{}", ident))
+        .collect();
+
+    // Bounds on the field type are made higher-ranked so that a bound which
+    // doesn't hold for a concrete field type just makes the bounded item
+    // unusable, instead of failing to compile (see rust-lang/rust#48214)
+    let bounded_field_ty = quote!(for<'__array_as_struct_bound> #field_ty);
 
-    let field_index = 0usize..;
     let field_count = vis_fields.len();
-    let field_count_str = field_count.to_string();
+    let field_names: Vec<_> = ident_fields
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect();
+    let ident_str = ident.unraw().to_string();
+    let field_index: Vec<usize> = (0..field_count).collect();
+    // The pairs of fields swapped to reverse them, outermost first
+    let (reverse_front, reverse_back): (Vec<_>, Vec<_>) = ident_fields
+        .iter()
+        .zip(ident_fields.iter().rev())
+        .take(field_count / 2)
+        .unzip();
+    let array_vis = (!options.private_array).then(|| quote!(pub));
+    let array_doc = format!("The array of the {field_count} field values, in declaration order");
 
-    abort_if_dirty();
+    // Without any fields, the reference types need something else to use
+    // their lifetime
+    let (lifetime_marker, lifetime_marker_init) = if field_count == 0 {
+        (
+            quote!(
+                #[doc(hidden)]
+                __marker: ::core::marker::PhantomData<&'__array_as_struct ()>
+            ),
+            quote!(__marker: ::core::marker::PhantomData),
+        )
+    } else {
+        (quote!(), quote!())
+    };
 
-    let v = quote!(
-        #(#attrs)*
-        #[repr(transparent)]
-        #vis struct #ident<#generic_params>(
-            /// The array of
-            #[doc = #field_count_str]
-            /// values
-            pub [#field_ty; #field_count]
-        );
+    let lhs_fields: Vec<_> = field_index
+        .iter()
+        .map(|i| format_ident!("__lhs_{}", i))
+        .collect();
+    let rhs_fields: Vec<_> = field_index
+        .iter()
+        .map(|i| format_ident!("__rhs_{}", i))
+        .collect();
 
-        impl<#generic_params> #ident<#generic_params_no_attr> {
-            #[inline(always)]
-            /// Construct the tuple-struct type from the named-field type
-            #vis const fn from_val(value: <Self as #found_crate::ArrayStruct>::Value) -> Self {
-                #(#attrs)*
-                #vis struct Value<#generic_params>{#(
-                    #(#attr_fields)*
-                    #vis_fields #ident_fields: #field_ty
-                ),*};
-                #[allow(dead_code)]
-                #vis struct Refs<'__array_as_struct, #generic_params>{#(
-                    #(#attr_fields)*
-                    #vis_fields #ident_fields: &'__array_as_struct #field_ty),*
-                };
-                #[allow(dead_code)]
-                #vis struct Muts<'__array_as_struct, #generic_params>{#(
-                    #(#attr_fields)*
-                    #vis_fields #ident_fields: &'__array_as_struct mut #field_ty),*
-                };
-                #[allow(dead_code)]
-                #vis struct Index;
-
-                impl<#generic_params> Value<#generic_params_no_attr> {
-                    ///
-                    #[inline(always)]
-                    pub const fn to_array_struct(self) -> #ident<#generic_params_no_attr> {
-                        #ident::from_val(self)
+    let ops_impls = options.ops.then(|| {
+        let ops = [
+            (quote!(Add), quote!(add), quote!(AddAssign), quote!(add_assign)),
+            (quote!(Sub), quote!(sub), quote!(SubAssign), quote!(sub_assign)),
+            (quote!(Mul), quote!(mul), quote!(MulAssign), quote!(mul_assign)),
+            (quote!(Div), quote!(div), quote!(DivAssign), quote!(div_assign)),
+        ];
+        let ops = ops.into_iter().map(|(op, op_fn, op_assign, op_assign_fn)| {
+            quote!(
+                impl<#generic_params> ::core::ops::#op for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op<Output = #field_ty>,
+                {
+                    type Output = Self;
+                    #inline
+                    fn #op_fn(self, rhs: Self) -> Self {
+                        let Self([#(#lhs_fields),*]) = self;
+                        let Self([#(#rhs_fields),*]) = rhs;
+                        Self([#(::core::ops::#op::#op_fn(#lhs_fields, #rhs_fields)),*])
                     }
                 }
 
-                impl Index {#(
-                    #[inline(always)]
-                    pub const fn #ident_fields() -> usize { #field_index }
+                impl<#generic_params> ::core::ops::#op<#field_ty> for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op<Output = #field_ty> + ::core::marker::Copy,
+                {
+                    type Output = Self;
+                    #inline
+                    fn #op_fn(self, rhs: #field_ty) -> Self {
+                        let Self([#(#lhs_fields),*]) = self;
+                        // The braces copy `rhs` rather than reborrowing it, in
+                        // case the field type is a mutable reference
+                        Self([#(::core::ops::#op::#op_fn(#lhs_fields, { rhs })),*])
+                    }
+                }
+
+                impl<#generic_params> ::core::ops::#op_assign<#field_ty> for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op<Output = #field_ty> + ::core::marker::Copy,
+                {
+                    #inline
+                    fn #op_assign_fn(&mut self, rhs: #field_ty) {
+                        for field in &mut self.0 {
+                            *field = ::core::ops::#op::#op_fn({ *field }, { rhs });
+                        }
+                    }
+                }
+            )
+        });
+        let lo_fields: Vec<_> = field_index
+            .iter()
+            .map(|i| format_ident!("__lo_{}", i))
+            .collect();
+        let hi_fields: Vec<_> = field_index
+            .iter()
+            .map(|i| format_ident!("__hi_{}", i))
+            .collect();
+        // `ops` is rejected without fields, so there's always a first product
+        let products = lhs_fields
+            .iter()
+            .zip(&rhs_fields)
+            .map(|(lhs, rhs)| quote!(::core::ops::Mul::mul(#lhs, #rhs)));
+        let dot = products
+            .reduce(|sum, product| quote!(::core::ops::Add::add(#sum, #product)))
+            .unwrap_or_default();
+        quote!(
+            #(#ops)*
+
+            impl<#generic_params> ::core::ops::Neg for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::ops::Neg<Output = #field_ty>,
+            {
+                type Output = Self;
+                #inline
+                fn neg(self) -> Self {
+                    let Self([#(#lhs_fields),*]) = self;
+                    Self([#(::core::ops::Neg::neg(#lhs_fields)),*])
+                }
+            }
+
+            impl<#generic_params> #ident<#generic_params_no_attr>
+            #where_clause
+            {
+                #inline_large
+                /// The dot product, summing the products of corresponding fields
+                #vis fn dot(&self, other: &Self) -> #field_ty
+                where
+                    #bounded_field_ty: ::core::ops::Mul<Output = #field_ty>
+                        + ::core::ops::Add<Output = #field_ty>
+                        + ::core::marker::Copy,
+                {
+                    let [#(#lhs_fields),*] = self.0;
+                    let [#(#rhs_fields),*] = other.0;
+                    #dot
+                }
+
+                #inline
+                /// The squared length, i.e. the dot product with itself. This is
+                /// cheaper than `length` for comparing lengths
+                #vis fn length_squared(&self) -> #field_ty
+                where
+                    #bounded_field_ty: ::core::ops::Mul<Output = #field_ty>
+                        + ::core::ops::Add<Output = #field_ty>
+                        + ::core::marker::Copy,
+                {
+                    self.dot(self)
+                }
+
+                #inline
+                /// The length, i.e. the square root of the sum of the squared fields
+                #vis fn length(&self) -> #field_ty
+                where
+                    #bounded_field_ty: #found_crate::Float,
+                {
+                    #found_crate::Float::sqrt(self.length_squared())
+                }
+
+                #inline_large
+                /// Scale the fields so the length is one. If the length is zero, the
+                /// struct is returned unchanged rather than dividing by zero
+                #[must_use]
+                #vis fn normalize(self) -> Self
+                where
+                    #bounded_field_ty: #found_crate::Float,
+                {
+                    let length = self.length();
+                    if length == <#field_ty as #found_crate::Float>::ZERO {
+                        return self;
+                    }
+                    let mut array = self.0;
+                    for field in &mut array {
+                        *field = ::core::ops::Div::div({ *field }, { length });
+                    }
+                    Self(array)
+                }
+
+                #inline_large
+                /// Clamp every field to the range `lo..=hi`. Panics if `lo > hi`
+                #[must_use]
+                #vis fn clamp_scalar(self, lo: #field_ty, hi: #field_ty) -> Self
+                where
+                    #bounded_field_ty: ::core::cmp::Ord + ::core::marker::Copy,
+                {
+                    let Self([#(#lhs_fields),*]) = self;
+                    // Copied up front so the bounds aren't reborrowed, in case the
+                    // field type is a mutable reference
+                    #(let (#lo_fields, #hi_fields) = (lo, hi);)*
+                    Self([#(::core::cmp::Ord::clamp(#lhs_fields, #lo_fields, #hi_fields)),*])
+                }
+
+                #inline_large
+                /// Clamp each field to the range between the corresponding fields of
+                /// `lo` and `hi`. Panics if any field of `lo` is greater than that of
+                /// `hi`. This shadows `Ord::clamp`, which can still be called as
+                /// `Ord::clamp(a, lo, hi)`
+                #[must_use]
+                #vis fn clamp(self, lo: Self, hi: Self) -> Self
+                where
+                    #bounded_field_ty: ::core::cmp::Ord,
+                {
+                    let Self([#(#lhs_fields),*]) = self;
+                    let Self([#(#lo_fields),*]) = lo;
+                    let Self([#(#hi_fields),*]) = hi;
+                    Self([#(::core::cmp::Ord::clamp(#lhs_fields, #lo_fields, #hi_fields)),*])
+                }
+            }
+        )
+    });
+
+    let bitops_impls = options.bitops.then(|| {
+        let ops = [
+            (
+                quote!(BitAnd),
+                quote!(bitand),
+                quote!(BitAndAssign),
+                quote!(bitand_assign),
+            ),
+            (
+                quote!(BitOr),
+                quote!(bitor),
+                quote!(BitOrAssign),
+                quote!(bitor_assign),
+            ),
+            (
+                quote!(BitXor),
+                quote!(bitxor),
+                quote!(BitXorAssign),
+                quote!(bitxor_assign),
+            ),
+        ];
+        let ops = ops.into_iter().map(|(op, op_fn, op_assign, op_assign_fn)| {
+            quote!(
+                impl<#generic_params> ::core::ops::#op for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op<Output = #field_ty>,
+                {
+                    type Output = Self;
+                    #inline
+                    fn #op_fn(self, rhs: Self) -> Self {
+                        let Self([#(#lhs_fields),*]) = self;
+                        let Self([#(#rhs_fields),*]) = rhs;
+                        Self([#(::core::ops::#op::#op_fn(#lhs_fields, #rhs_fields)),*])
+                    }
+                }
+
+                impl<#generic_params> ::core::ops::#op_assign for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op_assign,
+                {
+                    #inline
+                    fn #op_assign_fn(&mut self, rhs: Self) {
+                        for (field, rhs) in ::core::iter::Iterator::zip(self.0.iter_mut(), rhs.0) {
+                            ::core::ops::#op_assign::#op_assign_fn(field, rhs);
+                        }
+                    }
+                }
+            )
+        });
+        let shifts = [
+            (quote!(Shl), quote!(shl), quote!(ShlAssign), quote!(shl_assign)),
+            (quote!(Shr), quote!(shr), quote!(ShrAssign), quote!(shr_assign)),
+        ];
+        let shifts = shifts.into_iter().map(|(op, op_fn, op_assign, op_assign_fn)| {
+            quote!(
+                impl<#generic_params __R> ::core::ops::#op<__R> for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op<__R, Output = #field_ty>,
+                    __R: ::core::marker::Copy,
+                {
+                    type Output = Self;
+                    #inline
+                    fn #op_fn(self, rhs: __R) -> Self {
+                        let Self([#(#lhs_fields),*]) = self;
+                        Self([#(::core::ops::#op::#op_fn(#lhs_fields, rhs)),*])
+                    }
+                }
+
+                impl<#generic_params __R> ::core::ops::#op_assign<__R> for #ident<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: ::core::ops::#op_assign<__R>,
+                    __R: ::core::marker::Copy,
+                {
+                    #inline
+                    fn #op_assign_fn(&mut self, rhs: __R) {
+                        for field in &mut self.0 {
+                            ::core::ops::#op_assign::#op_assign_fn(field, rhs);
+                        }
+                    }
+                }
+            )
+        });
+        quote!(
+            #(#ops)*
+            #(#shifts)*
+
+            impl<#generic_params> ::core::ops::Not for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::ops::Not<Output = #field_ty>,
+            {
+                type Output = Self;
+                #inline
+                fn not(self) -> Self {
+                    let Self([#(#lhs_fields),*]) = self;
+                    Self([#(::core::ops::Not::not(#lhs_fields)),*])
+                }
+            }
+        )
+    });
+
+    let with_methods = options.with.then(|| {
+        quote!(#(
+            #inline
+            /// Replace this field, returning the modified struct
+            #vis fn #ident_fields_with(mut self, value: #field_ty) -> Self {
+                self.0[#field_index] = value;
+                self
+            }
+        )*)
+    });
+
+    let alloc_methods = cfg!(feature = "alloc").then(|| {
+        let alloc = quote!(#found_crate::__private::alloc);
+        quote!(
+            #inline_large
+            /// Move the fields into a map from their names to their values
+            #vis fn to_map(self) -> #alloc::collections::BTreeMap<&'static str, #field_ty> {
+                ::core::iter::Iterator::collect(::core::iter::Iterator::zip(
+                    ::core::iter::IntoIterator::into_iter(Self::NAMES),
+                    self.0,
+                ))
+            }
+        )
+    });
+
+    // Comparing with the `Value` helper goes field by field, so neither side
+    // has to be moved or cloned
+    let eq_value = if field_count == 0 {
+        quote!(true)
+    } else {
+        let comparisons = field_index
+            .iter()
+            .zip(&ident_fields)
+            .map(|(i, field)| quote!(::core::cmp::PartialEq::eq(&self.0[#i], &other.#field)));
+        quote!(#(#comparisons)&&*)
+    };
+
+    let alloc_impls = cfg!(feature = "alloc").then(|| {
+        let alloc = quote!(#found_crate::__private::alloc);
+        quote!(
+            impl<#generic_params> ::core::convert::TryFrom<#alloc::vec::Vec<#field_ty>> for #ident<#generic_params_no_attr>
+            #where_clause
+            {
+                type Error = #found_crate::LengthMismatch;
+
+                #inline_large
+                fn try_from(vec: #alloc::vec::Vec<#field_ty>) -> ::core::result::Result<Self, Self::Error> {
+                    let actual = vec.len();
+                    match ::core::convert::TryFrom::try_from(vec) {
+                        ::core::result::Result::Ok(array) => ::core::result::Result::Ok(Self(array)),
+                        ::core::result::Result::Err(_) => ::core::result::Result::Err(#found_crate::LengthMismatch {
+                            expected: #field_count,
+                            actual,
+                        }),
+                    }
+                }
+            }
+        )
+    });
+
+    // Rotating fewer than two fields does nothing, and `n % 1` would trip
+    // clippy in the user's crate
+    let (rotate_left, rotate_right) = match field_count {
+        0 | 1 => (quote!(let _ = n;), quote!(let _ = n;)),
+        _ => (
+            quote!(self.0.rotate_left(n % #field_count);),
+            quote!(self.0.rotate_right(n % #field_count);),
+        ),
+    };
+
+    // Tuples only implement the standard traits up to 12 elements, so larger
+    // ones aren't worth converting to, and the unit tuple isn't either
+    let tuple_methods = (1..=12).contains(&field_count).then(|| {
+        let field_tys = lhs_fields.iter().map(|_| &field_ty);
+        let tuple_ty = quote!((#(#field_tys,)*));
+        quote!(
+            #inline
+            /// Construct the tuple-struct type from a tuple of the fields, in
+            /// declaration order
+            #vis fn from_tuple((#(#lhs_fields,)*): #tuple_ty) -> Self {
+                Self([#(#lhs_fields),*])
+            }
+
+            #inline
+            /// Move the fields into a tuple, in declaration order
+            #vis fn into_tuple(self) -> #tuple_ty {
+                let Self([#(#lhs_fields),*]) = self;
+                (#(#lhs_fields,)*)
+            }
+        )
+    });
+
+    let swizzle_method = options.swizzle.then(|| {
+        let order: Vec<_> = field_index
+            .iter()
+            .map(|i| format_ident!("__I{}", i))
+            .collect();
+        quote!(
+            #inline
+            /// Construct a copy with the fields reordered, so that each field in
+            /// declaration order takes its value from the field at the
+            /// corresponding index, e.g. `swizzle::<2, 1, 0>()` reverses three
+            /// fields. Indices are checked at compile time
+            #vis fn swizzle<#(const #order: usize),*>(&self) -> Self
+            where
+                #bounded_field_ty: ::core::clone::Clone,
+            {
+                #(
+                    const {
+                        ::core::assert!(#order < #field_count, "swizzle index out of range");
+                    }
+                )*
+                Self([#(::core::clone::Clone::clone(&self.0[#order])),*])
+            }
+        )
+    });
+
+    let debug_impl = options.debug.then(|| {
+        quote!(
+            impl<#generic_params> ::core::fmt::Debug for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::fmt::Debug,
+            {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.debug_struct(#ident_str)
+                        #(.field(#field_names, &self.0[#field_index]))*
+                        .finish()
+                }
+            }
+        )
+    });
+
+    let display_impl = options.display.then(|| {
+        quote!(
+            impl<#generic_params> ::core::fmt::Display for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::fmt::Display,
+            {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    let separator = if f.alternate() { "\n" } else { ", " };
+                    for (i, (name, value)) in Self::NAMES.iter().zip(&self.0).enumerate() {
+                        if i != 0 {
+                            f.write_str(separator)?;
+                        }
+                        f.write_str(name)?;
+                        f.write_str("=")?;
+                        ::core::fmt::Display::fmt(value, f)?;
+                    }
+                    ::core::result::Result::Ok(())
+                }
+            }
+        )
+    });
+
+    let hash_impl = options.hash.then(|| {
+        quote!(
+            impl<#generic_params> ::core::hash::Hash for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::hash::Hash,
+            {
+                fn hash<__H: ::core::hash::Hasher>(&self, state: &mut __H) {
+                    ::core::hash::Hash::hash(&self.0, state)
+                }
+            }
+        )
+    });
+
+    let ord_impls = options.ord.then(|| {
+        // `PartialEq` and `Eq` are left up to the user to derive, so they're
+        // bounded the same way as the field type
+        let bounded_self = quote!(for<'__array_as_struct_bound> #ident<#generic_params_no_attr>);
+        quote!(
+            impl<#generic_params> ::core::cmp::PartialOrd for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::cmp::PartialOrd,
+                #bounded_self: ::core::cmp::PartialEq,
+            {
+                #inline_large
+                fn partial_cmp(&self, other: &Self) -> ::core::option::Option<::core::cmp::Ordering> {
+                    ::core::cmp::PartialOrd::partial_cmp(&self.0, &other.0)
+                }
+            }
+
+            impl<#generic_params> ::core::cmp::Ord for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: ::core::cmp::Ord,
+                #bounded_self: ::core::cmp::Eq,
+            {
+                #inline_large
+                fn cmp(&self, other: &Self) -> ::core::cmp::Ordering {
+                    ::core::cmp::Ord::cmp(&self.0, &other.0)
+                }
+            }
+        )
+    });
+
+    // The impls below take care of `serde` field attributes, which would be
+    // unknown on the helper types unless they happen to derive the traits too
+    let (serde_names, attr_fields): (Vec<_>, Vec<_>) = match options.serde {
+        Some(_) => attr_fields
+            .into_iter()
+            .zip(&field_names)
+            .map(|(attrs, name)| {
+                let serde_name = serde::field_rename(&attrs).unwrap_or_else(|| name.clone());
+                let attrs: Vec<_> = attrs
+                    .into_iter()
+                    .filter(|attr| !attr.path().is_ident("serde"))
+                    .collect();
+                (serde_name, attrs)
+            })
+            .unzip(),
+        None => (field_names.clone(), attr_fields),
+    };
+    let serde_impls = options.serde.map(|mode| match mode {
+        SerdeMode::Map => serde::map_impls(
+            &found_crate,
+            &ident,
+            &generic_params,
+            &generic_params_no_attr,
+            &where_clause,
+            &field_ty,
+            &serde_names,
+        ),
+        SerdeMode::Array => serde::array_impls(
+            &found_crate,
+            &ident,
+            &generic_params,
+            &generic_params_no_attr,
+            &where_clause,
+            &field_ty,
+            field_count,
+        ),
+    });
+
+    let bytemuck_impls = options.bytemuck.then(|| {
+        let bytemuck = quote!(#found_crate::__private::bytemuck);
+        // SAFETY: The struct is `#[repr(transparent)]` or `#[repr(C)]` over
+        // `[T; N]` (see `check_user_repr`), so has the same layout, and
+        // arrays are `Zeroable` and `Pod` whenever their element type is.
+        // `Pod` additionally requires the struct be `Copy`, which is left up to
+        // the user to derive
+        quote!(
+            unsafe impl<#generic_params> #bytemuck::Zeroable for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: #bytemuck::Zeroable,
+            {
+            }
+
+            unsafe impl<#generic_params> #bytemuck::Pod for #ident<#generic_params_no_attr>
+            #where_clause
+                #bounded_field_ty: #bytemuck::Pod,
+            {
+            }
+        )
+    });
+    let wrapper_impl = wrap_array.then(|| {
+        let bytemuck = quote!(#found_crate::__private::bytemuck);
+        // SAFETY: The struct is `#[repr(transparent)]` over `[T; N]`, its only
+        // field, which is exactly what `TransparentWrapper` requires
+        quote!(
+            unsafe impl<#generic_params> #bytemuck::TransparentWrapper<[#field_ty; #field_count]>
+                for #ident<#generic_params_no_attr>
+            #where_clause
+            {
+            }
+        )
+    });
+    let wrapper_methods = wrap_array.then(|| {
+        let bytemuck = quote!(#found_crate::__private::bytemuck);
+        quote!(
+            #inline
+            /// Reinterpret a reference to an array as a reference to the struct,
+            /// without copying
+            #vis fn from_array_ref(array: &[#field_ty; #field_count]) -> &Self {
+                #bytemuck::TransparentWrapper::wrap_ref(array)
+            }
+
+            #inline
+            /// Reinterpret a mutable reference to an array as a mutable reference
+            /// to the struct, without copying
+            #vis fn from_array_mut(array: &mut [#field_ty; #field_count]) -> &mut Self {
+                #bytemuck::TransparentWrapper::wrap_mut(array)
+            }
+        )
+    });
+
+    // Exposed helper types are prefixed with the struct name by default,
+    // since they share its module
+    let helper_name = |name: Option<Ident>, default| {
+        name.unwrap_or_else(|| match options.expose {
+            true => format_ident!("{}{}", ident.unraw(), default),
+            false => Ident::new(default, Span::call_site()),
+        })
+    };
+    // Keeping the named struct just exposes the `Value` helper under that name
+    let keep_named = options.keep_named.is_some();
+    let value_ty = match (options.keep_named, options.value) {
+        (Some(name), Some(value)) => {
+            emit_error!(value, "`value` can't be combined with `keep_named`");
+            name
+        }
+        (Some(name), None) => name,
+        (None, value) => helper_name(value, "Value"),
+    };
+    let refs_ty = helper_name(options.refs, "Refs");
+    let muts_ty = helper_name(options.muts, "Muts");
+    let index_ty = helper_name(options.index, "Index");
+
+    let refs_doc = format!(
+        "References to the fields of [`{}`] by name, which also iterate in declaration order",
+        ident.unraw()
+    );
+    let muts_doc = format!(
+        "Mutable references to the fields of [`{}`] by name, which also iterate in declaration order",
+        ident.unraw()
+    );
+    let field_index_docs: Vec<_> = field_names
+        .iter()
+        .map(|name| format!("The index of the `{name}` field"))
+        .collect();
+    let index_doc = format!(
+        "Functions returning the [`FieldIndex`]({}::FieldIndex) of each field of [`{}`]",
+        found_crate,
+        ident.unraw()
+    );
+    // The `Value` helper keeps the struct's attributes, with its derives
+    // swapped out if asked to. A `repr` describes the array layout, and
+    // `transparent` would be invalid on a struct of several fields
+    let value_attrs: Vec<Attribute> = match &options.value_derives {
+        Some(derives) => attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident("repr") && !attr.path().is_ident("derive"))
+            .cloned()
+            .chain((!derives.is_empty()).then(|| parse_quote!(#[derive(#derives)])))
+            .collect(),
+        None => attrs
+            .iter()
+            .filter(|attr| !attr.path().is_ident("repr"))
+            .cloned()
+            .collect(),
+    };
+    let value_helper = quote!(
+        #(#value_attrs)*
+        #vis struct #value_ty<#struct_generic_params>
+        #where_clause
+        {#(
+            #(#attr_fields)*
+            #vis_fields #ident_fields: #field_ty
+        ),*}
+        impl<#generic_params> #value_ty<#generic_params_no_attr>
+        #where_clause
+        {
+            ///
+            #inline
+            #vis fn to_array_struct(self) -> #ident<#generic_params_no_attr> {
+                #ident::from_val(self)
+            }
+        }
+    );
+    let helpers = quote!(
+        #[doc = #refs_doc]
+        #[allow(dead_code)]
+        #vis struct #refs_ty<'__array_as_struct, #struct_generic_params>
+        #where_clause
+        {
+            #lifetime_marker
+            #(
+                #(#attr_fields)*
+                #vis_fields #ident_fields: &'__array_as_struct #field_ty
+            ),*
+        }
+        #[doc = #muts_doc]
+        #[allow(dead_code)]
+        #vis struct #muts_ty<'__array_as_struct, #struct_generic_params>
+        #where_clause
+        {
+            #lifetime_marker
+            #(
+                #(#attr_fields)*
+                #vis_fields #ident_fields: &'__array_as_struct mut #field_ty
+            ),*
+        }
+        impl<'__array_as_struct, #generic_params> ::core::iter::IntoIterator
+            for #refs_ty<'__array_as_struct, #generic_params_no_attr>
+        #where_clause
+        {
+            type Item = &'__array_as_struct #field_ty;
+            type IntoIter = ::core::array::IntoIter<&'__array_as_struct #field_ty, #field_count>;
+            #inline
+            fn into_iter(self) -> Self::IntoIter {
+                let Self { #(#ident_fields,)* .. } = self;
+                ::core::iter::IntoIterator::into_iter([#(#ident_fields),*])
+            }
+        }
+        impl<'__array_as_struct, #generic_params> ::core::iter::IntoIterator
+            for #muts_ty<'__array_as_struct, #generic_params_no_attr>
+        #where_clause
+        {
+            type Item = &'__array_as_struct mut #field_ty;
+            type IntoIter = ::core::array::IntoIter<&'__array_as_struct mut #field_ty, #field_count>;
+            #inline
+            fn into_iter(self) -> Self::IntoIter {
+                let Self { #(#ident_fields,)* .. } = self;
+                ::core::iter::IntoIterator::into_iter([#(#ident_fields),*])
+            }
+        }
+        #[doc = #index_doc]
+        #[allow(dead_code)]
+        #vis struct #index_ty;
+
+        impl #index_ty {#(
+            #[doc = #field_index_docs]
+            #inline
+            #vis const fn #ident_fields<#generic_params>() -> #found_crate::FieldIndex<#ident<#generic_params_no_attr>>
+            #where_clause
+            {
+                #found_crate::FieldIndex::__new(#field_index)
+            }
+        )*}
+    );
+    // The helper types are declared inside `from_val` unless exposed, so they
+    // don't take up any names in the user's module
+    let (module_helpers, local_helpers) = match options.expose {
+        true => (Some(helpers), None),
+        false => (None, Some(helpers)),
+    };
+    let (module_value_helper, local_value_helper) = match options.expose || keep_named {
+        true => (Some(value_helper), None),
+        false => (None, Some(value_helper)),
+    };
+
+    let field_enum = format_ident!("{}Field", ident.unraw());
+    let field_enum_doc = format!("The fields of [`{}`], in declaration order", ident.unraw());
+    // A `repr` isn't allowed on an enum without variants
+    let field_enum_repr = (field_count != 0).then(|| quote!(#[repr(usize)]));
+
+    // Indexing is forwarded for each of these rather than for any type the
+    // array can be indexed by, which would overlap with `FieldIndex`
+    let slice_index_tys = [
+        quote!(usize),
+        quote!(::core::ops::Range<usize>),
+        quote!(::core::ops::RangeFrom<usize>),
+        quote!(::core::ops::RangeFull),
+        quote!(::core::ops::RangeInclusive<usize>),
+        quote!(::core::ops::RangeTo<usize>),
+        quote!(::core::ops::RangeToInclusive<usize>),
+        quote!((::core::ops::Bound<usize>, ::core::ops::Bound<usize>)),
+    ];
+
+    // Traits implemented below would conflict with a derive
+    let mut implemented_traits = vec!["Default"];
+    if options.debug {
+        implemented_traits.push("Debug");
+    }
+    if options.hash {
+        implemented_traits.push("Hash");
+    }
+    if options.ord {
+        implemented_traits.extend(["PartialOrd", "Ord"]);
+    }
+    // The user's docs go first, so they read as the struct's docs rather than
+    // being interleaved with other attributes
+    let (doc_attrs, struct_attrs): (Vec<_>, Vec<_>) = strip_derives(&attrs, &implemented_traits)
+        .into_iter()
+        .partition(|attr| attr.path().is_ident("doc"));
+
+    abort_if_dirty();
+
+    let v = quote!(
+        #(#doc_attrs)*
+        #(#struct_attrs)*
+        #repr
+        #vis struct #ident<#struct_generic_params>(
+            #[doc = #array_doc]
+            #array_vis [#field_ty; #field_count]
+        )
+        #where_clause;
+
+        #[doc = #field_enum_doc]
+        #[allow(non_camel_case_types)]
+        #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+        #field_enum_repr
+        #vis enum #field_enum {#(
+            #[allow(missing_docs)]
+            #ident_fields
+        ),*}
+
+        impl #field_enum {
+            #inline
+            /// The index of the field in the underlying array
+            #vis const fn index(self) -> usize {
+                match self {#(
+                    Self::#ident_fields => #field_index,
                 )*}
+            }
+
+            #inline
+            /// Get the field at `index`, or `None` if it's out of range
+            #vis const fn from_index(index: usize) -> ::core::option::Option<Self> {
+                match index {
+                    #(#field_index => ::core::option::Option::Some(Self::#ident_fields),)*
+                    _ => ::core::option::Option::None,
+                }
+            }
+        }
+
+        #module_value_helper
+        #module_helpers
 
-                impl<#generic_params> #found_crate::ArrayStruct for #ident<#generic_params_no_attr> {
-                    type Value = Value<#generic_params_no_attr>;
+        impl<#generic_params> #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
+            /// Construct the tuple-struct type from the named-field type.
+            ///
+            /// This isn't `const`, so that fields may need dropping. In const
+            /// contexts, use `new` instead
+            #vis fn from_val(value: <Self as #found_crate::ArrayStruct>::Value) -> Self {
+                #local_value_helper
+                #local_helpers
+                #[allow(non_local_definitions)]
+                impl<#generic_params> #found_crate::ArrayStruct for #ident<#generic_params_no_attr>
+                #where_clause
+                {
+                    type Value = #value_ty<#generic_params_no_attr>;
+                    type Element = #field_ty;
                     type Array = [#field_ty; #field_count];
-                    type Refs<'__array_as_struct> = Refs<'__array_as_struct, #generic_params_no_attr>;
-                    type Muts<'__array_as_struct> = Muts<'__array_as_struct, #generic_params_no_attr>;
-                    type Index = Index;
-                    #[inline(always)]
+                    type Refs<'__array_as_struct> = #refs_ty<'__array_as_struct, #generic_params_no_attr>
+                    where
+                        Self: '__array_as_struct;
+                    type Muts<'__array_as_struct> = #muts_ty<'__array_as_struct, #generic_params_no_attr>
+                    where
+                        Self: '__array_as_struct;
+                    type Index = #index_ty;
+                    #inline
                     fn from_val(value: Self::Value) -> Self {
                         <#ident::<#generic_params_no_attr>>::from_val(value)
                     }
-                    #[inline(always)]
+                    #inline
                     fn val(self) -> Self::Value {
                         <#ident::<#generic_params_no_attr>>::val(self)
                     }
-                    #[inline(always)]
+                    #inline
                     fn to_array(self) -> Self::Array {
                         self.0
                     }
-                    #[inline(always)]
+                    #inline
                     fn from_array(array: Self::Array) -> Self {
                         Self(array)
                     }
-                    #[inline(always)]
+                    #inline
                     fn refs(&'_ self) -> Self::Refs<'_> {
                         <#ident::<#generic_params_no_attr>>::refs(self)
                     }
-                    #[inline(always)]
+                    #inline
                     fn muts(&'_ mut self) -> Self::Muts<'_> {
                         <#ident::<#generic_params_no_attr>>::muts(self)
                     }
@@ -187,120 +1420,967 @@ fn array_as_struct_helper(_attr: TokenStream, item: TokenStream, doctest: bool)
                 Self([#(value.#ident_fields),*])
             }
 
-            #[inline(always)]
-            /// Construct the named-field type from the tuple-struct type
-            #vis const fn val(self) -> <Self as #found_crate::ArrayStruct>::Value {
+            #inline
+            /// Construct the named-field type from the tuple-struct type.
+            ///
+            /// This isn't `const`, so that fields may need dropping. In const
+            /// contexts, use `refs` or the field accessors instead
+            #vis fn val(self) -> <Self as #found_crate::ArrayStruct>::Value {
                 let Self([#(#ident_fields),*]) = self;
-                type Value = <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value;
-                Value {
+                #[allow(type_alias_bounds)]
+                type Value<#generic_params> = <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value;
+                Value::<#generic_params_no_attr> {
                     #(#ident_fields),*
                 }
             }
 
-            #[inline(always)]
+            #inline
             /// Construct the reference-named-field type from the tuple-struct type.
             #vis const fn refs(&'_ self) -> <Self as #found_crate::ArrayStruct>::Refs<'_> {
                 let Self([#(#ident_fields),*]) = self;
-                type Refs<'__array_as_struct> = <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Refs<'__array_as_struct>;
-                Refs {
+                #[allow(type_alias_bounds)]
+                type Refs<'__array_as_struct, #generic_params> = <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Refs<'__array_as_struct>;
+                Refs::<'_, #generic_params_no_attr> {
+                    #lifetime_marker_init
                     #(#ident_fields),*
                 }
             }
 
-            #[inline(always)]
-            /// Construct the mutable-reference-named-field type from the tuple-struct type
-            #vis fn muts(&'_ mut self) -> <Self as #found_crate::ArrayStruct>::Muts<'_> {
+            #inline
+            /// Construct the mutable-reference-named-field type from the tuple-struct type.
+            ///
+            /// Unlike `ArrayStruct::muts`, this can be called in const contexts.
+            #vis const fn muts(&'_ mut self) -> <Self as #found_crate::ArrayStruct>::Muts<'_> {
                 let Self([#(#ident_fields),*]) = self;
-                type Muts<'__array_as_struct> = <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Muts<'__array_as_struct>;
-                Muts {
+                #[allow(type_alias_bounds)]
+                type Muts<'__array_as_struct, #generic_params> = <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Muts<'__array_as_struct>;
+                Muts::<'_, #generic_params_no_attr> {
+                    #lifetime_marker_init
                     #(#ident_fields),*
                 }
             }
+
+            #inline
+            /// Construct the tuple-struct type from the value of each field, in
+            /// declaration order
+            #[allow(clippy::too_many_arguments)]
+            #vis const fn new(#(#ident_fields: #field_ty),*) -> Self {
+                Self([#(#ident_fields),*])
+            }
+
+            #inline
+            /// Construct the tuple-struct type by calling `f` with the index of
+            /// each field, in declaration order
+            #vis fn from_fn(f: impl FnMut(usize) -> #field_ty) -> Self {
+                Self(::core::array::from_fn(f))
+            }
+
+            #inline
+            /// Construct the tuple-struct type by converting each element of
+            /// `array` with [`Into`], in declaration order
+            #vis fn from_array_into<__U>(array: [__U; #field_count]) -> Self
+            where
+                __U: ::core::convert::Into<#field_ty>,
+            {
+                Self(array.map(::core::convert::Into::into))
+            }
+
+            #inline_large
+            /// Construct the tuple-struct type from the first items of `iter`, in
+            /// declaration order. Any further items are ignored, and if there
+            /// aren't enough items an error is returned
+            #vis fn try_from_iter(
+                iter: impl ::core::iter::IntoIterator<Item = #field_ty>,
+            ) -> ::core::result::Result<Self, #found_crate::LengthMismatch> {
+                let mut iter = iter.into_iter().fuse();
+                let mut actual = 0;
+                let array: [::core::option::Option<#field_ty>; #field_count] = ::core::array::from_fn(|_| {
+                    let item = iter.next();
+                    actual += item.is_some() as usize;
+                    item
+                });
+                if actual != #field_count {
+                    return ::core::result::Result::Err(#found_crate::LengthMismatch {
+                        expected: #field_count,
+                        actual,
+                    });
+                }
+                ::core::result::Result::Ok(Self(array.map(|item| match item {
+                    ::core::option::Option::Some(item) => item,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                })))
+            }
+
+            #inline
+            /// Construct the tuple-struct type with every field set to a clone of `value`
+            #vis fn splat(value: #field_ty) -> Self
+            where
+                #bounded_field_ty: ::core::clone::Clone,
+            {
+                Self(::core::array::from_fn(|_| ::core::clone::Clone::clone(&value)))
+            }
+
+            #(
+                #inline
+                /// Get a reference to this field
+                #vis const fn #ident_fields(&self) -> &#field_ty {
+                    &self.0[#field_index]
+                }
+
+                #inline
+                /// Get a mutable reference to this field
+                #vis const fn #ident_fields_mut(&mut self) -> &mut #field_ty {
+                    &mut self.0[#field_index]
+                }
+
+                #inline
+                /// Get a copy of this field
+                #vis const fn #ident_fields_copied(&self) -> #field_ty
+                where
+                    #bounded_field_ty: ::core::marker::Copy,
+                {
+                    // Binding first avoids reborrowing when the field type is
+                    // itself a mutable reference
+                    let value = self.0[#field_index];
+                    value
+                }
+
+                #inline
+                /// Replace the value of this field
+                #vis fn #ident_fields_set(&mut self, value: #field_ty) {
+                    self.0[#field_index] = value;
+                }
+            )*
+
+            #with_methods
+            #swizzle_method
+            #tuple_methods
+
+            #wrapper_methods
+
+            #inline
+            /// The number of fields
+            #vis const fn len(&self) -> usize {
+                #field_count
+            }
+
+            #inline
+            /// Whether there are no fields
+            #vis const fn is_empty(&self) -> bool {
+                #field_count == 0
+            }
+
+            #inline
+            /// Borrow the underlying array
+            #vis const fn to_array_ref(&self) -> &[#field_ty; #field_count] {
+                &self.0
+            }
+
+            #inline
+            /// Copy the underlying array, without consuming the struct
+            #vis const fn copy_array(&self) -> [#field_ty; #field_count]
+            where
+                #bounded_field_ty: ::core::marker::Copy,
+            {
+                self.0
+            }
+
+            #inline
+            /// Clone the underlying array, without consuming the struct. This
+            /// doesn't need the struct itself to be `Clone`, which still requires
+            /// a derive
+            #vis fn clone_array(&self) -> [#field_ty; #field_count]
+            where
+                #bounded_field_ty: ::core::clone::Clone,
+            {
+                ::core::clone::Clone::clone(&self.0)
+            }
+
+            #inline
+            /// View the fields as a slice, in declaration order
+            #vis const fn as_slice(&self) -> &[#field_ty] {
+                self.0.as_slice()
+            }
+
+            #inline
+            /// View the fields as a mutable slice, in declaration order
+            #vis const fn as_mut_slice(&mut self) -> &mut [#field_ty] {
+                &mut self.0
+            }
+
+            #inline
+            /// Iterate over the fields in groups of `size`, in declaration order.
+            /// The last group is shorter if `size` doesn't divide the number of
+            /// fields. Panics if `size` is zero
+            #vis fn chunks(&self, size: usize) -> ::core::slice::Chunks<'_, #field_ty> {
+                self.0.chunks(size)
+            }
+
+            #inline
+            /// Iterate over every run of `size` consecutive fields, in declaration
+            /// order. Panics if `size` is zero
+            #vis fn windows(&self, size: usize) -> ::core::slice::Windows<'_, #field_ty> {
+                self.0.windows(size)
+            }
+
+            #inline
+            /// Split off a reference to the first field from the rest, or `None`
+            /// if there are no fields
+            #vis const fn split_first(&self) -> ::core::option::Option<(&#field_ty, &[#field_ty])> {
+                self.0.split_first()
+            }
+
+            #inline
+            /// Split off a mutable reference to the first field from the rest, or
+            /// `None` if there are no fields
+            #vis const fn split_first_mut(&mut self) -> ::core::option::Option<(&mut #field_ty, &mut [#field_ty])> {
+                self.0.split_first_mut()
+            }
+
+            #inline
+            /// Split off a reference to the last field from the rest, or `None` if
+            /// there are no fields
+            #vis const fn split_last(&self) -> ::core::option::Option<(&#field_ty, &[#field_ty])> {
+                self.0.split_last()
+            }
+
+            #inline
+            /// Split off a mutable reference to the last field from the rest, or
+            /// `None` if there are no fields
+            #vis const fn split_last_mut(&mut self) -> ::core::option::Option<(&mut #field_ty, &mut [#field_ty])> {
+                self.0.split_last_mut()
+            }
+
+            #inline
+            /// Borrow each field, as an array in declaration order
+            #vis const fn each_ref(&self) -> [&#field_ty; #field_count] {
+                // Destructuring rather than forwarding to the array's `each_ref`
+                // keeps this const on older toolchains
+                let Self([#(#ident_fields),*]) = self;
+                [#(#ident_fields),*]
+            }
+
+            #inline
+            /// Mutably borrow each field, as an array in declaration order
+            #vis const fn each_mut(&mut self) -> [&mut #field_ty; #field_count] {
+                let Self([#(#ident_fields),*]) = self;
+                [#(#ident_fields),*]
+            }
+
+            #inline
+            /// Consume the struct, iterating over the owned fields in declaration
+            /// order. Any fields left unvisited are dropped in that same order
+            #vis fn into_values(self) -> ::core::array::IntoIter<#field_ty, #field_count> {
+                ::core::iter::IntoIterator::into_iter(self.0)
+            }
+
+            #inline
+            /// Iterate over the indices of the fields along with references to
+            /// them, in declaration order
+            #vis fn indexed_refs(&self) -> ::core::iter::Enumerate<::core::slice::Iter<'_, #field_ty>> {
+                self.0.iter().enumerate()
+            }
+
+            #inline
+            /// Call `f` on a mutable reference to each field in declaration order
+            #vis fn map_in_place(&mut self, f: impl FnMut(&mut #field_ty)) {
+                self.0.iter_mut().for_each(f)
+            }
+
+            #inline
+            /// Whether `f` holds for every field, stopping at the first which fails
+            #vis fn all(&self, f: impl FnMut(&#field_ty) -> bool) -> bool {
+                self.0.iter().all(f)
+            }
+
+            #inline
+            /// Whether `f` holds for any field, stopping at the first which does
+            #vis fn any(&self, f: impl FnMut(&#field_ty) -> bool) -> bool {
+                self.0.iter().any(f)
+            }
+
+            #inline
+            /// Reduce the fields in declaration order to a single value by
+            /// repeatedly applying `f`, or `None` if there are no fields
+            #vis fn reduce(self, f: impl FnMut(#field_ty, #field_ty) -> #field_ty) -> ::core::option::Option<#field_ty> {
+                ::core::iter::IntoIterator::into_iter(self.0).reduce(f)
+            }
+
+            #inline
+            /// The smallest field, or `None` if there are no fields. The first is
+            /// returned if several are equally small
+            #vis fn min_element(&self) -> ::core::option::Option<&#field_ty>
+            where
+                #bounded_field_ty: ::core::cmp::Ord,
+            {
+                self.0.iter().min()
+            }
+
+            #inline
+            /// The largest field, or `None` if there are no fields. The last is
+            /// returned if several are equally large
+            #vis fn max_element(&self) -> ::core::option::Option<&#field_ty>
+            where
+                #bounded_field_ty: ::core::cmp::Ord,
+            {
+                self.0.iter().max()
+            }
+
+            #inline
+            /// The smallest field according to `compare`, or `None` if there are
+            /// no fields. This works for fields which aren't `Ord`, like floats
+            #vis fn min_element_by(
+                &self,
+                mut compare: impl FnMut(&#field_ty, &#field_ty) -> ::core::cmp::Ordering,
+            ) -> ::core::option::Option<&#field_ty> {
+                self.0.iter().min_by(|a, b| compare(a, b))
+            }
+
+            #inline
+            /// The largest field according to `compare`, or `None` if there are
+            /// no fields. This works for fields which aren't `Ord`, like floats
+            #vis fn max_element_by(
+                &self,
+                mut compare: impl FnMut(&#field_ty, &#field_ty) -> ::core::cmp::Ordering,
+            ) -> ::core::option::Option<&#field_ty> {
+                self.0.iter().max_by(|a, b| compare(a, b))
+            }
+
+            #inline
+            /// Construct an array by applying `f` to each field in declaration order
+            #vis fn map_array<__U>(self, f: impl FnMut(#field_ty) -> __U) -> [__U; #field_count] {
+                self.0.map(f)
+            }
+
+            #inline
+            /// Construct an array by applying `f` to the index and value of each
+            /// field in declaration order
+            #vis fn map_array_with_index<__U>(self, mut f: impl FnMut(usize, #field_ty) -> __U) -> [__U; #field_count] {
+                let mut index = 0;
+                self.0.map(|field| {
+                    let mapped = f(index, field);
+                    index += 1;
+                    mapped
+                })
+            }
+
+            #inline
+            /// Construct another array-struct of the same shape by applying `f` to
+            /// each field in declaration order.
+            ///
+            /// This is useful to convert between parallel declarations which only
+            /// differ by field type, e.g. integer and float representations. It
+            /// isn't named `map`, so that `map` on the underlying array is still
+            /// reachable through `Deref`
+            #vis fn map_fields<__U, __S>(self, f: impl FnMut(#field_ty) -> __U) -> __S
+            where
+                __S: #found_crate::ArrayStruct<Array = [__U; #field_count]>,
+            {
+                __S::from_array(self.0.map(f))
+            }
+
+            #inline_large
+            /// Construct an array by applying the fallible `f` to each field in
+            /// declaration order, stopping at and returning the first error
+            #vis fn try_map_array<__U, __E>(
+                self,
+                mut f: impl FnMut(#field_ty) -> ::core::result::Result<__U, __E>,
+            ) -> ::core::result::Result<[__U; #field_count], __E> {
+                let mut error = ::core::option::Option::None;
+                let array = self.0.map(|value| {
+                    if error.is_some() {
+                        return ::core::option::Option::None;
+                    }
+                    match f(value) {
+                        ::core::result::Result::Ok(value) => ::core::option::Option::Some(value),
+                        ::core::result::Result::Err(err) => {
+                            error = ::core::option::Option::Some(err);
+                            ::core::option::Option::None
+                        }
+                    }
+                });
+                if let ::core::option::Option::Some(err) = error {
+                    return ::core::result::Result::Err(err);
+                }
+                ::core::result::Result::Ok(array.map(|value| match value {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                }))
+            }
+
+            #inline
+            /// Construct another array-struct of the same shape by applying the
+            /// fallible `f` to each field in declaration order, stopping at and
+            /// returning the first error
+            #vis fn try_map<__U, __E, __S>(
+                self,
+                f: impl FnMut(#field_ty) -> ::core::result::Result<__U, __E>,
+            ) -> ::core::result::Result<__S, __E>
+            where
+                __S: #found_crate::ArrayStruct<Array = [__U; #field_count]>,
+            {
+                self.try_map_array(f).map(__S::from_array)
+            }
+
+            #inline
+            /// Iterate over pairs of references to the fields of `self` and `other`,
+            /// in declaration order
+            #vis fn zip<'__array_as_struct>(
+                &'__array_as_struct self,
+                other: &'__array_as_struct Self,
+            ) -> ::core::iter::Zip<
+                ::core::slice::Iter<'__array_as_struct, #field_ty>,
+                ::core::slice::Iter<'__array_as_struct, #field_ty>,
+            > {
+                self.0.iter().zip(other.0.iter())
+            }
+
+            #inline
+            /// Iterate over pairs of fields of `self` and `other`, in declaration order
+            #vis fn zip_values(
+                self,
+                other: Self,
+            ) -> ::core::iter::Zip<
+                ::core::array::IntoIter<#field_ty, #field_count>,
+                ::core::array::IntoIter<#field_ty, #field_count>,
+            > {
+                self.0.into_iter().zip(other.0)
+            }
+
+            #inline
+            /// Swap the fields at indices `a` and `b`, panicking if either is out
+            /// of bounds
+            #vis const fn swap(&mut self, a: usize, b: usize) {
+                self.0.swap(a, b)
+            }
+
+            #inline
+            /// Reverse the order of the fields in place
+            #vis const fn reverse(&mut self) {
+                // Swapping the destructured fields rather than forwarding to the
+                // slice's `reverse` keeps this const on older toolchains
+                #[allow(unused_variables)]
+                let Self([#(#ident_fields),*]) = self;
+                #(::core::mem::swap(#reverse_front, #reverse_back);)*
+            }
+
+            #inline
+            /// Reverse the order of the fields, returning the modified struct
+            #[must_use]
+            #vis fn reversed(mut self) -> Self {
+                self.0.reverse();
+                self
+            }
+
+            #inline
+            /// Overwrite every field with a clone of `value`. Unlike `splat`, this
+            /// reuses the existing struct
+            #vis fn fill(&mut self, value: #field_ty)
+            where
+                #bounded_field_ty: ::core::clone::Clone,
+            {
+                self.0.fill(value)
+            }
+
+            #inline
+            /// Shift the fields `n` places towards the start, wrapping around to
+            /// the end, returning the modified struct. `rotate_left` on the
+            /// underlying slice rotates in place instead
+            #[must_use]
+            #vis fn rotated_left(mut self, n: usize) -> Self {
+                #rotate_left
+                self
+            }
+
+            #inline
+            /// Shift the fields `n` places towards the end, wrapping around to the
+            /// start, returning the modified struct. `rotate_right` on the
+            /// underlying slice rotates in place instead
+            #[must_use]
+            #vis fn rotated_right(mut self, n: usize) -> Self {
+                #rotate_right
+                self
+            }
+
+            #inline_large
+            /// Append the `M` values of `other` to the fields, producing an array
+            /// of length `R`.
+            ///
+            /// Since arithmetic on const generics isn't stable, the length can't be
+            /// written as `N + M`. Instead `R` is usually inferred from the use
+            /// site, and is checked to equal `N + M` at compile time
+            #vis fn concat_array<const __M: usize, const __R: usize>(
+                self,
+                other: [#field_ty; __M],
+            ) -> [#field_ty; __R] {
+                const {
+                    ::core::assert!(
+                        #field_count + __M == __R,
+                        "the length of the concatenated array must be the sum of the lengths",
+                    );
+                }
+                let mut head = ::core::iter::IntoIterator::into_iter(self.0);
+                let mut tail = ::core::iter::IntoIterator::into_iter(other);
+                ::core::array::from_fn(|_| match head.next() {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => match tail.next() {
+                        ::core::option::Option::Some(value) => value,
+                        ::core::option::Option::None => ::core::unreachable!(),
+                    },
+                })
+            }
+
+            #inline_large
+            /// Split the fields into an array of the first `K` and an array of the
+            /// remaining `R`.
+            ///
+            /// Since arithmetic on const generics isn't stable, the length of the
+            /// second array can't be written as `N - K`, so both lengths are const
+            /// arguments. `R` can be inferred from the type of the result, and is
+            /// checked to equal `N - K` at compile time
+            #vis fn split_array<const __K: usize, const __R: usize>(self) -> ([#field_ty; __K], [#field_ty; __R]) {
+                const {
+                    ::core::assert!(
+                        __K + __R == #field_count,
+                        "the lengths of the split arrays must add up to the number of fields",
+                    );
+                }
+                let mut fields = ::core::iter::IntoIterator::into_iter(self.0);
+                let mut next = || match fields.next() {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                };
+                let head = ::core::array::from_fn(|_| next());
+                let tail = ::core::array::from_fn(|_| next());
+                (head, tail)
+            }
+
+            #inline_large
+            /// Move the fields into a matrix of `ROWS` rows of `COLS` fields each,
+            /// filling each row in turn in declaration order.
+            ///
+            /// `ROWS * COLS` is checked to equal the number of fields at compile
+            /// time
+            #vis fn to_matrix<const __ROWS: usize, const __COLS: usize>(self) -> [[#field_ty; __COLS]; __ROWS] {
+                const {
+                    ::core::assert!(
+                        __ROWS * __COLS == #field_count,
+                        "the matrix must have exactly one element for each field",
+                    );
+                }
+                let mut fields = ::core::iter::IntoIterator::into_iter(self.0);
+                let mut next = || match fields.next() {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => ::core::unreachable!(),
+                };
+                ::core::array::from_fn(|_| ::core::array::from_fn(|_| next()))
+            }
+
+            #inline_large
+            /// Clone the fields at each of `indices` into an array, in the order
+            /// given, panicking with the offending index if any is out of bounds
+            #vis fn project<const __K: usize>(&self, indices: [usize; __K]) -> [#field_ty; __K]
+            where
+                #bounded_field_ty: ::core::clone::Clone,
+            {
+                indices.map(|index| match self.0.get(index) {
+                    ::core::option::Option::Some(value) => ::core::clone::Clone::clone(value),
+                    ::core::option::Option::None => ::core::panic!(
+                        "field index {} is out of range for `{}` with {} fields",
+                        index,
+                        #ident_str,
+                        #field_count,
+                    ),
+                })
+            }
+
+            #inline
+            /// Get a reference to the field at `index`, or to the fields in a range
+            /// of indices, or `None` if it's out of range, like `get` on a slice
+            #vis fn get<__I>(&self, index: __I) -> ::core::option::Option<&<__I as ::core::slice::SliceIndex<[#field_ty]>>::Output>
+            where
+                __I: ::core::slice::SliceIndex<[#field_ty]>,
+            {
+                self.0.get(index)
+            }
+
+            #inline
+            /// Get a mutable reference to the field at `index`, or to the fields in
+            /// a range of indices, or `None` if it's out of range, like `get_mut`
+            /// on a slice
+            #vis fn get_mut<__I>(&mut self, index: __I) -> ::core::option::Option<&mut <__I as ::core::slice::SliceIndex<[#field_ty]>>::Output>
+            where
+                __I: ::core::slice::SliceIndex<[#field_ty]>,
+            {
+                self.0.get_mut(index)
+            }
+
+            /// The names of the fields, in declaration order
+            #vis const NAMES: [&'static str; #field_count] = [#(#field_names),*];
+
+            /// The byte offset of each field within the struct, in declaration
+            /// order. Since the struct has the layout of its array, these are just
+            /// multiples of the field type's size
+            #vis const FIELD_OFFSETS: [usize; #field_count] =
+                [#(#field_index * ::core::mem::size_of::<#field_ty>()),*];
+
+            #inline
+            /// Get the name of the field at `index`, or `None` if it's out of range
+            #vis const fn name_of(index: usize) -> ::core::option::Option<&'static str> {
+                if index < #field_count {
+                    ::core::option::Option::Some(Self::NAMES[index])
+                } else {
+                    ::core::option::Option::None
+                }
+            }
+
+            #inline
+            /// Get the index of the field called `name`, or `None` if there's no such field
+            #vis fn index_of(name: &str) -> ::core::option::Option<usize> {
+                Self::NAMES.iter().position(|field_name| *field_name == name)
+            }
+
+            #inline
+            /// Get a reference to the field called `name`, or `None` if there's no
+            /// such field
+            #vis fn get_by_name(&self, name: &str) -> ::core::option::Option<&#field_ty> {
+                Self::index_of(name).map(|index| &self.0[index])
+            }
+
+            #inline
+            /// Get a mutable reference to the field called `name`, or `None` if
+            /// there's no such field
+            #vis fn get_by_name_mut(&mut self, name: &str) -> ::core::option::Option<&mut #field_ty> {
+                Self::index_of(name).map(|index| &mut self.0[index])
+            }
+
+            #inline
+            /// Iterate over the names of the fields along with references to
+            /// them, in declaration order
+            #vis fn named_refs(&self) -> impl ::core::iter::Iterator<Item = (&'static str, &#field_ty)> + '_ {
+                ::core::iter::Iterator::zip(::core::iter::IntoIterator::into_iter(Self::NAMES), self.0.iter())
+            }
+
+            #inline
+            /// Iterate over the names of the fields along with mutable references
+            /// to them, in declaration order
+            #vis fn named_muts(&mut self) -> impl ::core::iter::Iterator<Item = (&'static str, &mut #field_ty)> + '_ {
+                ::core::iter::Iterator::zip(::core::iter::IntoIterator::into_iter(Self::NAMES), self.0.iter_mut())
+            }
+
+            #inline
+            /// Iterate over the names and indices of the fields along with mutable
+            /// references to them, in declaration order
+            #vis fn enumerate_named_mut(&mut self) -> impl ::core::iter::Iterator<Item = (&'static str, usize, &mut #field_ty)> + '_ {
+                self.0
+                    .iter_mut()
+                    .enumerate()
+                    .map(|(index, field)| (Self::NAMES[index], index, field))
+            }
+
+            #inline
+            /// Get the name of the first field for which `pred` holds, or `None`
+            /// if it holds for none of them
+            #vis fn find_name(&self, pred: impl FnMut(&#field_ty) -> bool) -> ::core::option::Option<&'static str> {
+                self.0.iter().position(pred).map(|index| Self::NAMES[index])
+            }
+
+            #alloc_methods
         }
 
-        impl<#generic_params> ::core::convert::From<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value> for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<#generic_params> ::core::convert::From<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn from(value: <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value) -> Self {
                 Self::from_val(value)
             }
         }
-        impl<#generic_params> ::core::convert::From<#ident<#generic_params_no_attr>> for <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value {
-            #[inline(always)]
+        impl<#generic_params> ::core::convert::From<#ident<#generic_params_no_attr>> for <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value
+        #where_clause
+        {
+            #inline
             fn from(strct: #ident<#generic_params_no_attr>) -> Self {
                 strct.val()
             }
         }
 
-        impl<#generic_params> ::core::convert::From<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<#generic_params> ::core::convert::From<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn from(array: <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array) -> Self {
                 Self(array)
             }
         }
-        impl<#generic_params> ::core::convert::From<#ident<#generic_params_no_attr>> for <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array {
-            #[inline(always)]
+        impl<#generic_params> ::core::convert::From<#ident<#generic_params_no_attr>> for <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array
+        #where_clause
+        {
+            #inline
             fn from(strct: #ident<#generic_params_no_attr>) -> Self {
                 strct.0
             }
         }
 
-        impl<#generic_params> ::core::convert::AsRef<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<'__array_as_struct, #generic_params> ::core::convert::TryFrom<&'__array_as_struct [#field_ty]> for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: ::core::clone::Clone,
+        {
+            type Error = #found_crate::LengthMismatch;
+
+            #inline_large
+            fn try_from(slice: &'__array_as_struct [#field_ty]) -> ::core::result::Result<Self, Self::Error> {
+                if slice.len() != #field_count {
+                    return ::core::result::Result::Err(#found_crate::LengthMismatch {
+                        expected: #field_count,
+                        actual: slice.len(),
+                    });
+                }
+                ::core::result::Result::Ok(Self(::core::array::from_fn(|i| {
+                    ::core::clone::Clone::clone(&slice[i])
+                })))
+            }
+        }
+
+        /// Collects the first items of the iterator, in declaration order, and
+        /// ignores any further items.
+        ///
+        /// # Panics
+        ///
+        /// Panics if there aren't enough items. See `try_from_iter` for a
+        /// non-panicking alternative
+        impl<#generic_params> ::core::iter::FromIterator<#field_ty> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline_large
+            fn from_iter<__T>(iter: __T) -> Self
+            where
+                __T: ::core::iter::IntoIterator<Item = #field_ty>,
+            {
+                match Self::try_from_iter(iter) {
+                    ::core::result::Result::Ok(strct) => strct,
+                    ::core::result::Result::Err(err) => ::core::panic!(
+                        "not enough items to collect into `{}`: {}",
+                        #ident_str,
+                        err,
+                    ),
+                }
+            }
+        }
+
+        impl<#generic_params> ::core::cmp::PartialEq<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: ::core::cmp::PartialEq,
+        {
+            #inline_large
+            fn eq(&self, other: &<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array) -> bool {
+                self.0 == *other
+            }
+        }
+        impl<#generic_params> ::core::cmp::PartialEq<#ident<#generic_params_no_attr>> for <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array
+        #where_clause
+            #bounded_field_ty: ::core::cmp::PartialEq,
+        {
+            #inline_large
+            fn eq(&self, other: &#ident<#generic_params_no_attr>) -> bool {
+                *self == other.0
+            }
+        }
+
+        impl<#generic_params> ::core::cmp::PartialEq<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value> for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: ::core::cmp::PartialEq,
+        {
+            #inline_large
+            fn eq(&self, other: &<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value) -> bool {
+                #eq_value
+            }
+        }
+        impl<#generic_params> ::core::cmp::PartialEq<#ident<#generic_params_no_attr>> for <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Value
+        #where_clause
+            #bounded_field_ty: ::core::cmp::PartialEq,
+        {
+            #inline_large
+            fn eq(&self, other: &#ident<#generic_params_no_attr>) -> bool {
+                other == self
+            }
+        }
+
+        impl<#generic_params> ::core::convert::AsRef<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn as_ref(&self) -> &<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array {
                 &self.0
             }
         }
-        impl<#generic_params> ::core::convert::AsMut<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<#generic_params> ::core::convert::AsMut<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn as_mut(&mut self) -> &mut <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array {
                 &mut self.0
             }
         }
 
-        impl<#generic_params> ::core::borrow::Borrow<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<#generic_params> ::core::borrow::Borrow<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn borrow(&self) -> &<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array {
                 &self.0
             }
         }
-        impl<#generic_params> ::core::borrow::BorrowMut<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<#generic_params> ::core::borrow::BorrowMut<<#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn borrow_mut(&mut self) -> &mut <#ident::<#generic_params_no_attr> as #found_crate::ArrayStruct>::Array {
                 &mut self.0
             }
         }
 
-        impl<#generic_params> ::core::ops::Deref for #ident<#generic_params_no_attr> {
+        impl<#generic_params> ::core::ops::Deref for #ident<#generic_params_no_attr>
+        #where_clause
+        {
             type Target = [#field_ty; #field_count];
-            #[inline(always)]
+            #inline
             fn deref(&self) -> &Self::Target {
                 &self.0
             }
         }
-        impl<#generic_params> ::core::ops::DerefMut for #ident<#generic_params_no_attr> {
-            #[inline(always)]
+        impl<#generic_params> ::core::ops::DerefMut for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
             fn deref_mut(&mut self) -> &mut Self::Target {
                 &mut self.0
             }
         }
 
-        impl<I> core::ops::Index<I> for #ident<#generic_params_no_attr>
-        where [#field_ty; #field_count]: core::ops::Index<I> {
-            type Output = <[#field_ty; #field_count] as core::ops::Index<I>>::Output;
+        impl<#generic_params> ::core::default::Default for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: ::core::default::Default,
+        {
+            #inline
+            fn default() -> Self {
+                Self(::core::array::from_fn(|_| ::core::default::Default::default()))
+            }
+        }
+
+        impl<#generic_params> ::core::iter::IntoIterator for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            type Item = #field_ty;
+            type IntoIter = ::core::array::IntoIter<#field_ty, #field_count>;
+            #inline
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.into_iter()
+            }
+        }
+        impl<'__array_as_struct, #generic_params> ::core::iter::IntoIterator for &'__array_as_struct #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            type Item = &'__array_as_struct #field_ty;
+            type IntoIter = ::core::slice::Iter<'__array_as_struct, #field_ty>;
+            #inline
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter()
+            }
+        }
+        impl<'__array_as_struct, #generic_params> ::core::iter::IntoIterator for &'__array_as_struct mut #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            type Item = &'__array_as_struct mut #field_ty;
+            type IntoIter = ::core::slice::IterMut<'__array_as_struct, #field_ty>;
+            #inline
+            fn into_iter(self) -> Self::IntoIter {
+                self.0.iter_mut()
+            }
+        }
+
+        impl<#generic_params> ::core::ops::Index<#found_crate::FieldIndex<Self>> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            type Output = #field_ty;
 
-            #[inline(always)]
-            fn index(&self, index: I) -> &Self::Output {
-                &self.0[index]
+            #inline
+            fn index(&self, index: #found_crate::FieldIndex<Self>) -> &Self::Output {
+                &self.0[index.get()]
             }
         }
 
-        impl<I> core::ops::IndexMut<I> for #ident<#generic_params_no_attr>
-        where [#field_ty; #field_count]: core::ops::IndexMut<I> {
-            #[inline(always)]
-            fn index_mut(&mut self, index: I) -> &mut Self::Output {
-                &mut self.0[index]
+        impl<#generic_params> ::core::ops::IndexMut<#found_crate::FieldIndex<Self>> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline
+            fn index_mut(&mut self, index: #found_crate::FieldIndex<Self>) -> &mut Self::Output {
+                &mut self.0[index.get()]
             }
         }
+
+        impl<'__array_as_struct, #generic_params> ::core::ops::Index<&'__array_as_struct str> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            type Output = #field_ty;
+
+            #inline_large
+            fn index(&self, name: &'__array_as_struct str) -> &Self::Output {
+                match self.get_by_name(name) {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => {
+                        ::core::panic!("`{}` has no field named `{}`", #ident_str, name)
+                    }
+                }
+            }
+        }
+
+        impl<'__array_as_struct, #generic_params> ::core::ops::IndexMut<&'__array_as_struct str> for #ident<#generic_params_no_attr>
+        #where_clause
+        {
+            #inline_large
+            fn index_mut(&mut self, name: &'__array_as_struct str) -> &mut Self::Output {
+                match self.get_by_name_mut(name) {
+                    ::core::option::Option::Some(value) => value,
+                    ::core::option::Option::None => {
+                        ::core::panic!("`{}` has no field named `{}`", #ident_str, name)
+                    }
+                }
+            }
+        }
+
+        #(
+            impl<#generic_params> ::core::ops::Index<#slice_index_tys> for #ident<#generic_params_no_attr>
+            #where_clause
+            {
+                type Output = <[#field_ty] as ::core::ops::Index<#slice_index_tys>>::Output;
+
+                #inline
+                fn index(&self, index: #slice_index_tys) -> &Self::Output {
+                    &self.0[index]
+                }
+            }
+
+            impl<#generic_params> ::core::ops::IndexMut<#slice_index_tys> for #ident<#generic_params_no_attr>
+            #where_clause
+            {
+                #inline
+                fn index_mut(&mut self, index: #slice_index_tys) -> &mut Self::Output {
+                    &mut self.0[index]
+                }
+            }
+        )*
+
+        #ops_impls
+        #bitops_impls
+        #debug_impl
+        #display_impl
+        #hash_impl
+        #ord_impls
+        #serde_impls
+        #bytemuck_impls
+        #wrapper_impl
+        #alloc_impls
     );
 
     v.into()
@@ -0,0 +1,200 @@
+use syn::meta::ParseNestedMeta;
+use syn::punctuated::Punctuated;
+use syn::{parenthesized, Ident, LitInt, LitStr, Path, Token, Type};
+
+/// How to implement `Serialize` and `Deserialize`
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum SerdeMode {
+    /// As a map of field names to values, like the original declaration
+    Map,
+    /// As the underlying array
+    Array,
+}
+
+/// Which `#[inline]` attribute to put on the generated methods
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum InlineMode {
+    /// `#[inline(always)]` on small methods and `#[inline]` on the rest
+    Always,
+    /// `#[inline]` on every method
+    Hint,
+    /// `#[inline(never)]` on every method
+    Never,
+}
+
+/// Options passed to the `#[array_as_struct(...)]` attribute
+#[derive(Default)]
+pub struct Options {
+    /// Whether to implement the elementwise arithmetic operators
+    pub ops: bool,
+    /// Whether to implement the elementwise bitwise operators
+    pub bitops: bool,
+    /// Whether to implement `Debug` using the field names
+    pub debug: bool,
+    /// Whether to implement `Hash` like the underlying array
+    pub hash: bool,
+    /// Whether to implement `Display` using the field names
+    pub display: bool,
+    /// Whether to implement `PartialOrd` and `Ord` like the underlying array
+    pub ord: bool,
+    /// Whether to generate the `with_<field>` methods
+    pub with: bool,
+    /// Whether to generate the `swizzle` method
+    pub swizzle: bool,
+    /// How to implement `Serialize` and `Deserialize`, if at all
+    pub serde: Option<SerdeMode>,
+    /// Whether to implement `bytemuck::Zeroable` and `bytemuck::Pod`
+    pub bytemuck: bool,
+    /// The type of every field, required when there are no fields
+    pub element: Option<Type>,
+    /// The `repr` to emit instead of `transparent`
+    pub repr: Option<LitStr>,
+    /// The alignment to over-align the struct to
+    pub align: Option<LitInt>,
+    /// Whether to declare the helper types at module scope
+    pub expose: bool,
+    /// Whether to leave the array field private
+    pub private_array: bool,
+    /// How to mark the generated methods for inlining
+    pub inline: Option<InlineMode>,
+    /// The name to keep the original named-field struct under
+    pub keep_named: Option<Ident>,
+    /// The name of the `Value` helper type
+    pub value: Option<Ident>,
+    /// The derives to put on the `Value` helper type instead of the struct's
+    pub value_derives: Option<Punctuated<Path, Token![,]>>,
+    /// The name of the `Refs` helper type
+    pub refs: Option<Ident>,
+    /// The name of the `Muts` helper type
+    pub muts: Option<Ident>,
+    /// The name of the `Index` helper type
+    pub index: Option<Ident>,
+}
+
+impl Options {
+    /// Parses a single option, for use with [`syn::meta::parser`]
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("ops") {
+            set_flag(&mut self.ops, &meta)
+        } else if meta.path.is_ident("bitops") {
+            set_flag(&mut self.bitops, &meta)
+        } else if meta.path.is_ident("debug") {
+            set_flag(&mut self.debug, &meta)
+        } else if meta.path.is_ident("hash") {
+            set_flag(&mut self.hash, &meta)
+        } else if meta.path.is_ident("display") {
+            set_flag(&mut self.display, &meta)
+        } else if meta.path.is_ident("ord") {
+            set_flag(&mut self.ord, &meta)
+        } else if meta.path.is_ident("with") {
+            set_flag(&mut self.with, &meta)
+        } else if meta.path.is_ident("swizzle") {
+            set_flag(&mut self.swizzle, &meta)
+        } else if meta.path.is_ident("serde") {
+            if !cfg!(feature = "serde") {
+                return Err(meta
+                    .error("the `serde` option requires the `serde` feature of array-as-struct"));
+            }
+            let mode = if meta.input.peek(Token![=]) {
+                let mode: LitStr = meta.value()?.parse()?;
+                match mode.value().as_str() {
+                    "map" => SerdeMode::Map,
+                    "array" => SerdeMode::Array,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            mode,
+                            r#"expected "map" or "array""#,
+                        ))
+                    }
+                }
+            } else {
+                SerdeMode::Map
+            };
+            set_value(&mut self.serde, mode, &meta)
+        } else if meta.path.is_ident("bytemuck") {
+            if !cfg!(feature = "bytemuck") {
+                return Err(meta.error(
+                    "the `bytemuck` option requires the `bytemuck` feature of array-as-struct",
+                ));
+            }
+            set_flag(&mut self.bytemuck, &meta)
+        } else if meta.path.is_ident("element") {
+            let ty: Type = meta.value()?.parse()?;
+            set_value(&mut self.element, ty, &meta)
+        } else if meta.path.is_ident("repr") {
+            let repr: LitStr = meta.value()?.parse()?;
+            if !matches!(repr.value().as_str(), "C" | "transparent") {
+                return Err(syn::Error::new_spanned(
+                    repr,
+                    r#"expected "C" or "transparent""#,
+                ));
+            }
+            set_value(&mut self.repr, repr, &meta)
+        } else if meta.path.is_ident("align") {
+            let content;
+            parenthesized!(content in meta.input);
+            let align: LitInt = content.parse()?;
+            if !align.base10_parse::<u32>()?.is_power_of_two() {
+                return Err(syn::Error::new_spanned(align, "expected a power of two"));
+            }
+            set_value(&mut self.align, align, &meta)
+        } else if meta.path.is_ident("expose") {
+            set_flag(&mut self.expose, &meta)
+        } else if meta.path.is_ident("inline") {
+            let mode: LitStr = meta.value()?.parse()?;
+            let mode = match mode.value().as_str() {
+                "always" => InlineMode::Always,
+                "hint" => InlineMode::Hint,
+                "never" => InlineMode::Never,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        mode,
+                        r#"expected "always", "hint", or "never""#,
+                    ))
+                }
+            };
+            set_value(&mut self.inline, mode, &meta)
+        } else if meta.path.is_ident("private_array") {
+            set_flag(&mut self.private_array, &meta)
+        } else if meta.path.is_ident("keep_named") {
+            set_value(&mut self.keep_named, parse_name(&meta)?, &meta)
+        } else if meta.path.is_ident("value") {
+            set_value(&mut self.value, parse_name(&meta)?, &meta)
+        } else if meta.path.is_ident("value_derives") {
+            let content;
+            parenthesized!(content in meta.input);
+            let derives = Punctuated::parse_terminated(&content)?;
+            set_value(&mut self.value_derives, derives, &meta)
+        } else if meta.path.is_ident("refs") {
+            set_value(&mut self.refs, parse_name(&meta)?, &meta)
+        } else if meta.path.is_ident("muts") {
+            set_value(&mut self.muts, parse_name(&meta)?, &meta)
+        } else if meta.path.is_ident("index") {
+            set_value(&mut self.index, parse_name(&meta)?, &meta)
+        } else {
+            Err(meta.error("unsupported array_as_struct option"))
+        }
+    }
+}
+
+fn set_flag(flag: &mut bool, meta: &ParseNestedMeta) -> syn::Result<()> {
+    if *flag {
+        return Err(meta.error("duplicate array_as_struct option"));
+    }
+    *flag = true;
+    Ok(())
+}
+
+fn set_value<T>(slot: &mut Option<T>, value: T, meta: &ParseNestedMeta) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(meta.error("duplicate array_as_struct option"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+/// Parses a type name given as a string, e.g. `value = "FooData"`
+fn parse_name(meta: &ParseNestedMeta) -> syn::Result<Ident> {
+    let name: LitStr = meta.value()?.parse()?;
+    name.parse()
+}
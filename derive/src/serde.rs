@@ -0,0 +1,316 @@
+use proc_macro2::{Literal, TokenStream};
+use proc_macro_error::emit_error;
+use quote::{format_ident, quote};
+use syn::ext::IdentExt;
+use syn::punctuated::Punctuated;
+use syn::{Attribute, GenericArgument, GenericParam, Ident, LitStr, Token, Type};
+
+/// Finds the name given to a field by a `#[serde(rename = "...")]` attribute.
+/// Any other `serde` field attribute is reported, since the generated impls
+/// wouldn't honor it.
+pub fn field_rename(attrs: &[Attribute]) -> Option<String> {
+    let mut rename = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let result = attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("rename") {
+                return Err(
+                    meta.error("only `rename` is supported on the fields of an array-struct")
+                );
+            }
+            let name: LitStr = meta.value()?.parse()?;
+            if rename.is_some() {
+                return Err(meta.error("duplicate serde attribute `rename`"));
+            }
+            rename = Some(name.value());
+            Ok(())
+        });
+        if let Err(err) = result {
+            emit_error!(err.span(), "{}", err);
+        }
+    }
+    rename
+}
+
+/// Implements `Serialize` and `Deserialize` for the array-struct as if it was
+/// the original named-field struct, i.e. as a map from field names to values.
+pub fn map_impls(
+    found_crate: &Ident,
+    ident: &Ident,
+    generic_params: &Punctuated<GenericParam, Token![,]>,
+    generic_params_no_attr: &Punctuated<GenericArgument, Token![,]>,
+    where_clause: &TokenStream,
+    field_ty: &Type,
+    field_names: &[String],
+) -> TokenStream {
+    let serde = quote!(#found_crate::__private::serde);
+    let bounded_field_ty = quote!(for<'__array_as_struct_bound> #field_ty);
+    let ident_str = ident.unraw().to_string();
+    let expecting = format!("struct {ident_str}");
+    let field_count = field_names.len();
+    let field_index: Vec<usize> = (0..field_count).collect();
+    let field_index_u64: Vec<_> = field_index
+        .iter()
+        .map(|&i| Literal::u64_unsuffixed(i as u64))
+        .collect();
+    let field_vars: Vec<_> = field_index
+        .iter()
+        .map(|i| format_ident!("__field_{}", i))
+        .collect();
+    let field_bytes: Vec<_> = field_names
+        .iter()
+        .map(|name| Literal::byte_string(name.as_bytes()))
+        .collect();
+
+    quote!(
+        impl<#generic_params> #serde::Serialize for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: #serde::Serialize,
+        {
+            fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: #serde::Serializer,
+            {
+                let mut state = #serde::Serializer::serialize_struct(serializer, #ident_str, #field_count)?;
+                #(
+                    #serde::ser::SerializeStruct::serialize_field(&mut state, #field_names, &self.0[#field_index])?;
+                )*
+                #serde::ser::SerializeStruct::end(state)
+            }
+        }
+
+        impl<'__de, #generic_params> #serde::Deserialize<'__de> for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: #serde::Deserialize<'__de>,
+        {
+            fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+            where
+                __D: #serde::Deserializer<'__de>,
+            {
+                /// The index of a known field, or `None` for an ignored one
+                struct __Field(::core::option::Option<usize>);
+
+                struct __FieldVisitor;
+
+                impl<'__de> #serde::de::Visitor<'__de> for __FieldVisitor {
+                    type Value = __Field;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.write_str("field identifier")
+                    }
+
+                    fn visit_u64<__E>(self, value: u64) -> ::core::result::Result<__Field, __E>
+                    where
+                        __E: #serde::de::Error,
+                    {
+                        ::core::result::Result::Ok(__Field(match value {
+                            #(#field_index_u64 => ::core::option::Option::Some(#field_index),)*
+                            _ => ::core::option::Option::None,
+                        }))
+                    }
+
+                    fn visit_str<__E>(self, value: &str) -> ::core::result::Result<__Field, __E>
+                    where
+                        __E: #serde::de::Error,
+                    {
+                        ::core::result::Result::Ok(__Field(match value {
+                            #(#field_names => ::core::option::Option::Some(#field_index),)*
+                            _ => ::core::option::Option::None,
+                        }))
+                    }
+
+                    fn visit_bytes<__E>(self, value: &[u8]) -> ::core::result::Result<__Field, __E>
+                    where
+                        __E: #serde::de::Error,
+                    {
+                        ::core::result::Result::Ok(__Field(match value {
+                            #(#field_bytes => ::core::option::Option::Some(#field_index),)*
+                            _ => ::core::option::Option::None,
+                        }))
+                    }
+                }
+
+                impl<'__de> #serde::Deserialize<'__de> for __Field {
+                    fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+                    where
+                        __D: #serde::Deserializer<'__de>,
+                    {
+                        #serde::Deserializer::deserialize_identifier(deserializer, __FieldVisitor)
+                    }
+                }
+
+                struct __Visitor<#generic_params>(::core::marker::PhantomData<fn() -> #ident<#generic_params_no_attr>>)
+                #where_clause;
+
+                impl<'__de, #generic_params> #serde::de::Visitor<'__de> for __Visitor<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: #serde::Deserialize<'__de>,
+                {
+                    type Value = #ident<#generic_params_no_attr>;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.write_str(#expecting)
+                    }
+
+                    fn visit_seq<__A>(self, mut seq: __A) -> ::core::result::Result<Self::Value, __A::Error>
+                    where
+                        __A: #serde::de::SeqAccess<'__de>,
+                    {
+                        #(
+                            let #field_vars = match #serde::de::SeqAccess::next_element(&mut seq)? {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => {
+                                    return ::core::result::Result::Err(
+                                        #serde::de::Error::invalid_length(#field_index, &self),
+                                    );
+                                }
+                            };
+                        )*
+                        ::core::result::Result::Ok(#ident([#(#field_vars),*]))
+                    }
+
+                    fn visit_map<__A>(self, mut map: __A) -> ::core::result::Result<Self::Value, __A::Error>
+                    where
+                        __A: #serde::de::MapAccess<'__de>,
+                    {
+                        #(
+                            let mut #field_vars: ::core::option::Option<#field_ty> = ::core::option::Option::None;
+                        )*
+                        while let ::core::option::Option::Some(__Field(key)) = #serde::de::MapAccess::next_key(&mut map)? {
+                            match key {
+                                #(
+                                    ::core::option::Option::Some(#field_index) => {
+                                        if #field_vars.is_some() {
+                                            return ::core::result::Result::Err(
+                                                <__A::Error as #serde::de::Error>::duplicate_field(#field_names),
+                                            );
+                                        }
+                                        #field_vars = ::core::option::Option::Some(#serde::de::MapAccess::next_value(&mut map)?);
+                                    }
+                                )*
+                                _ => {
+                                    #serde::de::MapAccess::next_value::<#serde::de::IgnoredAny>(&mut map)?;
+                                }
+                            }
+                        }
+                        #(
+                            let #field_vars = match #field_vars {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => {
+                                    return ::core::result::Result::Err(
+                                        <__A::Error as #serde::de::Error>::missing_field(#field_names),
+                                    );
+                                }
+                            };
+                        )*
+                        ::core::result::Result::Ok(#ident([#(#field_vars),*]))
+                    }
+                }
+
+                #serde::Deserializer::deserialize_struct(
+                    deserializer,
+                    #ident_str,
+                    &[#(#field_names),*],
+                    __Visitor(::core::marker::PhantomData),
+                )
+            }
+        }
+    )
+}
+
+/// Implements `Serialize` and `Deserialize` for the array-struct as its
+/// underlying array, i.e. as a sequence of values in declaration order.
+pub fn array_impls(
+    found_crate: &Ident,
+    ident: &Ident,
+    generic_params: &Punctuated<GenericParam, Token![,]>,
+    generic_params_no_attr: &Punctuated<GenericArgument, Token![,]>,
+    where_clause: &TokenStream,
+    field_ty: &Type,
+    field_count: usize,
+) -> TokenStream {
+    let serde = quote!(#found_crate::__private::serde);
+    let bounded_field_ty = quote!(for<'__array_as_struct_bound> #field_ty);
+    let expecting = format!(
+        "an array of length {field_count} for struct {}",
+        ident.unraw()
+    );
+    let field_index: Vec<usize> = (0..field_count).collect();
+    let field_vars: Vec<_> = field_index
+        .iter()
+        .map(|i| format_ident!("__field_{}", i))
+        .collect();
+
+    quote!(
+        impl<#generic_params> #serde::Serialize for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: #serde::Serialize,
+        {
+            fn serialize<__S>(&self, serializer: __S) -> ::core::result::Result<__S::Ok, __S::Error>
+            where
+                __S: #serde::Serializer,
+            {
+                let mut state = #serde::Serializer::serialize_tuple(serializer, #field_count)?;
+                for field in &self.0 {
+                    #serde::ser::SerializeTuple::serialize_element(&mut state, field)?;
+                }
+                #serde::ser::SerializeTuple::end(state)
+            }
+        }
+
+        impl<'__de, #generic_params> #serde::Deserialize<'__de> for #ident<#generic_params_no_attr>
+        #where_clause
+            #bounded_field_ty: #serde::Deserialize<'__de>,
+        {
+            fn deserialize<__D>(deserializer: __D) -> ::core::result::Result<Self, __D::Error>
+            where
+                __D: #serde::Deserializer<'__de>,
+            {
+                struct __Visitor<#generic_params>(::core::marker::PhantomData<fn() -> #ident<#generic_params_no_attr>>)
+                #where_clause;
+
+                impl<'__de, #generic_params> #serde::de::Visitor<'__de> for __Visitor<#generic_params_no_attr>
+                #where_clause
+                    #bounded_field_ty: #serde::Deserialize<'__de>,
+                {
+                    type Value = #ident<#generic_params_no_attr>;
+
+                    fn expecting(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                        f.write_str(#expecting)
+                    }
+
+                    fn visit_seq<__A>(self, mut seq: __A) -> ::core::result::Result<Self::Value, __A::Error>
+                    where
+                        __A: #serde::de::SeqAccess<'__de>,
+                    {
+                        #(
+                            let #field_vars = match #serde::de::SeqAccess::next_element(&mut seq)? {
+                                ::core::option::Option::Some(value) => value,
+                                ::core::option::Option::None => {
+                                    return ::core::result::Result::Err(
+                                        #serde::de::Error::invalid_length(#field_index, &self),
+                                    );
+                                }
+                            };
+                        )*
+                        let mut len = #field_count;
+                        while #serde::de::SeqAccess::next_element::<#serde::de::IgnoredAny>(&mut seq)?.is_some() {
+                            len += 1;
+                        }
+                        if len != #field_count {
+                            return ::core::result::Result::Err(
+                                #serde::de::Error::invalid_length(len, &self),
+                            );
+                        }
+                        ::core::result::Result::Ok(#ident([#(#field_vars),*]))
+                    }
+                }
+
+                #serde::Deserializer::deserialize_tuple(
+                    deserializer,
+                    #field_count,
+                    __Visitor(::core::marker::PhantomData),
+                )
+            }
+        }
+    )
+}
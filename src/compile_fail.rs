@@ -0,0 +1,220 @@
+//! Misuses of the attribute which must be rejected, checked as `compile_fail`
+//! doctests
+
+/// A field index of one struct can't index another
+///
+/// ```compile_fail
+/// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct};
+/// #[array_as_struct]
+/// pub struct Foo {
+///     bar: u32,
+///     baz: u32,
+/// }
+///
+/// #[array_as_struct]
+/// pub struct Qux {
+///     quux: u32,
+///     corge: u32,
+/// }
+///
+/// let q = Qux([1, 2]);
+/// let _ = q[<Foo as ArrayStruct>::Index::bar()];
+/// ```
+pub struct ForeignFieldIndex;
+
+/// With the `bytemuck` option, a `repr` which may add padding is rejected
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct(bytemuck)]
+/// #[derive(Clone, Copy)]
+/// #[repr(C, align(16))]
+/// pub struct Padded {
+///     a: u8,
+///     b: u8,
+/// }
+/// ```
+#[cfg(feature = "bytemuck")]
+pub struct PaddedBytemuckRepr;
+
+/// A field can't share its name with the setter of another field
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct]
+/// pub struct Setter {
+///     bar: u32,
+///     set_bar: u32,
+/// }
+/// ```
+pub struct SetterCollision;
+
+/// A field can't share its name with the copying accessor of another field
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct]
+/// pub struct Copied {
+///     bar: u32,
+///     bar_copied: u32,
+/// }
+/// ```
+pub struct CopiedCollision;
+
+/// Swizzle indices are checked at compile time
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct(swizzle)]
+/// pub struct Pair {
+///     a: u8,
+///     b: u8,
+/// }
+///
+/// let _ = Pair([1, 2]).swizzle::<1, 2>();
+/// ```
+pub struct SwizzleOutOfRange;
+
+/// The length of a concatenated array must add up
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct]
+/// pub struct Pair {
+///     a: u8,
+///     b: u8,
+/// }
+///
+/// let _: [u8; 5] = Pair([1, 2]).concat_array([3, 4]);
+/// ```
+pub struct ConcatLengthMismatch;
+
+/// The lengths of split arrays must add up, which is checked when the split
+/// is instantiated
+///
+/// ```compile_fail,E0080
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct]
+/// pub struct Pair {
+///     a: u8,
+///     b: u8,
+/// }
+///
+/// let _: ([u8; 1], [u8; 2]) = Pair([1, 2]).split_array();
+/// ```
+pub struct SplitLengthMismatch;
+
+/// Fields can't be conditionally compiled, since the length of the array
+/// wouldn't follow
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct]
+/// pub struct Conditional {
+///     a: u8,
+///     #[cfg(any())]
+///     b: u8,
+/// }
+/// ```
+pub struct CfgField;
+
+/// Arithmetic on a struct without fields would be vacuous
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct(ops, element = f32)]
+/// pub struct Empty {}
+/// ```
+pub struct EmptyOps;
+
+/// With `private_array`, the array can't be reached from outside its module
+///
+/// ```compile_fail
+/// mod encapsulated {
+///     # use array_as_struct::array_as_struct_doctest as array_as_struct;
+///     #[array_as_struct(private_array)]
+///     pub struct Interval {
+///         start: u32,
+///         end: u32,
+///     }
+/// }
+///
+/// let i = encapsulated::Interval([1, 5]);
+/// ```
+pub struct PrivateArray;
+
+/// `repr(transparent)` can't be combined with an alignment
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct(align(16), repr = "transparent")]
+/// pub struct Lanes {
+///     x: f32,
+///     y: f32,
+/// }
+/// ```
+pub struct TransparentAlign;
+
+/// With the `bytemuck` option, over-aligning the struct is rejected, since it
+/// may add padding
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct(bytemuck, align(16))]
+/// #[derive(Clone, Copy)]
+/// pub struct Lanes {
+///     x: f32,
+///     y: f32,
+///     z: f32,
+/// }
+/// ```
+#[cfg(feature = "bytemuck")]
+pub struct AlignedBytemuck;
+
+/// A hand-written `ArrayStruct` impl must use an array of its `Element` as
+/// its `Array`
+///
+/// ```compile_fail
+/// use array_as_struct::ArrayStruct;
+///
+/// pub struct Pair(Vec<u32>);
+///
+/// impl ArrayStruct for Pair {
+///     type Value = ();
+///     type Element = u32;
+///     type Array = Vec<u32>;
+///     type Refs<'a> = ();
+///     type Muts<'a> = ();
+///     type Index = ();
+///
+///     fn from_val(_: ()) -> Self {
+///         unimplemented!()
+///     }
+///     fn val(self) {}
+///     fn from_array(array: Vec<u32>) -> Self {
+///         Pair(array)
+///     }
+///     fn to_array(self) -> Vec<u32> {
+///         self.0
+///     }
+///     fn refs(&self) {}
+///     fn muts(&mut self) {}
+/// }
+/// ```
+pub struct NonArrayShape;
+
+/// A matrix must have exactly as many elements as there are fields
+///
+/// ```compile_fail
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// #[array_as_struct]
+/// pub struct Quad {
+///     a: u32,
+///     b: u32,
+///     c: u32,
+///     d: u32,
+/// }
+///
+/// let _ = Quad([1, 2, 3, 4]).to_matrix::<3, 2>();
+/// ```
+pub struct MatrixShape;
@@ -2,13 +2,42 @@
 #![forbid(unsafe_code)]
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::marker::PhantomData;
+
 /// A trait to name all the associated types and simplify the conversion to and
 /// from the helper types.
 pub trait ArrayStruct {
     /// Helper type which is identical to the original field-struct declaration
     type Value;
-    /// The underlying array type
-    type Array;
+    /// The type of every field
+    ///
+    /// ```
+    /// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct};
+    /// # mod _hider{
+    /// use array_as_struct::{array_as_struct, ArrayStruct};
+    /// # }
+    ///
+    /// #[array_as_struct]
+    /// pub struct Foo {
+    ///     bar: f32,
+    ///     baz: f32,
+    /// }
+    ///
+    /// fn total<T: ArrayStruct<Element = f32>>(t: T) -> f32 {
+    ///     t.to_array().as_ref().iter().sum()
+    /// }
+    ///
+    /// assert_eq!(total(Foo([1.5, 2.0])), 3.5);
+    /// ```
+    type Element;
+    /// The underlying array type, which is always `[Element; FIELD_COUNT]`,
+    /// so can be viewed as a slice of [`Element`](ArrayStruct::Element)s
+    type Array: ArrayShape<Element = Self::Element>;
     /// Helper type which is similar to the original field-struct declaration,
     /// but with `&'a T` instead of `T` for the field type
     type Refs<'a>
@@ -20,8 +49,29 @@ pub trait ArrayStruct {
     where
         Self: 'a;
 
-    /// Helper type which contains helper functions to get the index of each field
-    /// by name.
+    /// The number of fields, which is also the length of the underlying array
+    ///
+    /// ```
+    /// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct};
+    /// # mod _hider{
+    /// use array_as_struct::{array_as_struct, ArrayStruct};
+    /// # }
+    ///
+    /// #[array_as_struct]
+    /// pub struct Foo {
+    ///     bar: u32,
+    ///     baz: u32,
+    /// }
+    ///
+    /// assert_eq!(<Foo as ArrayStruct>::FIELD_COUNT, 2);
+    /// ```
+    ///
+    /// This is taken from the length of [`Array`](ArrayStruct::Array), and
+    /// shouldn't be overridden.
+    const FIELD_COUNT: usize = <Self::Array as ArrayShape>::LEN;
+
+    /// Helper type which contains helper functions to get the [`FieldIndex`] of
+    /// each field by name.
     ///
     /// ```
     /// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct};
@@ -138,10 +188,381 @@ pub trait ArrayStruct {
     fn refs(&'_ self) -> Self::Refs<'_>;
 
     /// Construct the mutable-reference-named-field type from the tuple-struct type
+    ///
+    /// Trait methods can't be `const`, but the generated struct also has an
+    /// inherent `const fn muts` which can be used in const contexts
     fn muts(&'_ mut self) -> Self::Muts<'_>;
+
+    /// Convert to another tuple-struct type over the same array type, keeping
+    /// the values in the same positions
+    ///
+    /// ```
+    /// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct};
+    /// # mod _hider{
+    /// use array_as_struct::{array_as_struct, ArrayStruct};
+    /// # }
+    ///
+    /// #[array_as_struct]
+    /// pub struct Rgb {
+    ///     r: f32,
+    ///     g: f32,
+    ///     b: f32,
+    /// }
+    ///
+    /// #[array_as_struct]
+    /// pub struct Hsv {
+    ///     h: f32,
+    ///     s: f32,
+    ///     v: f32,
+    /// }
+    ///
+    /// let hsv: Hsv = Rgb([0.5, 1.0, 0.25]).reinterpret();
+    /// assert_eq!(hsv.s(), &1.0);
+    /// ```
+    #[inline(always)]
+    fn reinterpret<U>(self) -> U
+    where
+        Self: Sized,
+        U: ArrayStruct<Array = Self::Array>,
+    {
+        U::from_array(self.to_array())
+    }
+}
+
+/// Helpers which work the same way for every array-struct, so they're
+/// implemented once here rather than generated for each struct.
+///
+/// This is implemented for every [`ArrayStruct`] which can borrow its
+/// underlying array both immutably and mutably, which includes every struct
+/// generated by the macro.
+///
+/// ```
+/// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct, ArrayStructExt};
+/// # mod _hider{
+/// use array_as_struct::{array_as_struct, ArrayStructExt};
+/// # }
+///
+/// #[array_as_struct]
+/// pub struct Foo {
+///     bar: u32,
+///     baz: u32,
+/// }
+///
+/// let mut f = Foo([10, 15]);
+/// f.iter_mut().for_each(|x| *x += 1);
+/// assert!(f.iter().eq(&[11, 16]));
+/// assert!(f.contains(&16));
+/// assert_eq!(f.sum::<u32>(), 27);
+/// ```
+pub trait ArrayStructExt:
+    ArrayStruct + AsRef<<Self as ArrayStruct>::Array> + AsMut<<Self as ArrayStruct>::Array>
+{
+    /// Iterate over references to the fields in declaration order.
+    ///
+    /// This is the canonical way of walking the fields, and doesn't rely on
+    /// `Deref` to the underlying array.
+    #[inline(always)]
+    fn iter(&self) -> core::slice::Iter<'_, Self::Element> {
+        AsRef::<Self::Array>::as_ref(self).as_ref().iter()
+    }
+
+    /// Iterate over mutable references to the fields in declaration order
+    #[inline(always)]
+    fn iter_mut(&mut self) -> core::slice::IterMut<'_, Self::Element> {
+        AsMut::<Self::Array>::as_mut(self).as_mut().iter_mut()
+    }
+
+    /// Fold references to the fields in declaration order into a single value,
+    /// starting from `init`
+    #[inline(always)]
+    fn fold<B>(&self, init: B, f: impl FnMut(B, &Self::Element) -> B) -> B {
+        self.iter().fold(init, f)
+    }
+
+    /// Whether any field equals `value`
+    #[inline(always)]
+    fn contains(&self, value: &Self::Element) -> bool
+    where
+        Self::Element: PartialEq,
+    {
+        AsRef::<Self::Array>::as_ref(self).as_ref().contains(value)
+    }
+
+    /// Sum the fields
+    #[inline(always)]
+    fn sum<S>(self) -> S
+    where
+        Self: Sized,
+        Self::Array: IntoIterator<Item = Self::Element>,
+        S: core::iter::Sum<Self::Element>,
+    {
+        self.to_array().into_iter().sum()
+    }
+
+    /// Multiply the fields together
+    #[inline(always)]
+    fn product<S>(self) -> S
+    where
+        Self: Sized,
+        Self::Array: IntoIterator<Item = Self::Element>,
+        S: core::iter::Product<Self::Element>,
+    {
+        self.to_array().into_iter().product()
+    }
+}
+
+impl<S> ArrayStructExt for S where
+    S: ArrayStruct + AsRef<<S as ArrayStruct>::Array> + AsMut<<S as ArrayStruct>::Array>
+{
+}
+
+mod sealed {
+    pub trait Sealed {}
+
+    impl<T, const N: usize> Sealed for [T; N] {}
+}
+
+/// The shape of the underlying array of an [`ArrayStruct`], which is only
+/// implemented for arrays, so a hand-written [`ArrayStruct`] impl can't claim
+/// any other type as its [`Array`](ArrayStruct::Array)
+///
+/// ```
+/// use array_as_struct::ArrayShape;
+///
+/// assert_eq!(<[f32; 3] as ArrayShape>::LEN, 3);
+/// ```
+pub trait ArrayShape: sealed::Sealed + AsRef<[Self::Element]> + AsMut<[Self::Element]> {
+    /// The type of every element
+    type Element;
+
+    /// The length of the array
+    const LEN: usize;
+}
+
+impl<T, const N: usize> ArrayShape for [T; N] {
+    type Element = T;
+
+    const LEN: usize = N;
+}
+
+/// The index of a field of the array-struct `S`, which can only be used to
+/// index that struct
+///
+/// ```
+/// # use array_as_struct::{array_as_struct_doctest as array_as_struct, ArrayStruct};
+/// # mod _hider{
+/// use array_as_struct::{array_as_struct, ArrayStruct};
+/// # }
+///
+/// #[array_as_struct]
+/// pub struct Foo {
+///     bar: u32,
+///     baz: u32,
+/// }
+///
+/// let mut f = Foo([1, 2]);
+/// f[<Foo as ArrayStruct>::Index::baz()] = 3;
+/// assert_eq!(f.0, [1, 3]);
+/// assert_eq!(<Foo as ArrayStruct>::Index::baz().get(), 1);
+/// ```
+pub struct FieldIndex<S> {
+    index: usize,
+    _struct: PhantomData<fn() -> S>,
+}
+
+impl<S> FieldIndex<S> {
+    #[doc(hidden)]
+    #[inline(always)]
+    pub const fn __new(index: usize) -> Self {
+        Self {
+            index,
+            _struct: PhantomData,
+        }
+    }
+
+    /// The index into the underlying array
+    #[inline(always)]
+    pub const fn get(self) -> usize {
+        self.index
+    }
+}
+
+// These are implemented manually, since derives would require `S` to
+// implement the traits too
+
+impl<S> Clone for FieldIndex<S> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S> Copy for FieldIndex<S> {}
+
+impl<S> PartialEq for FieldIndex<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<S> Eq for FieldIndex<S> {}
+
+impl<S> PartialEq<usize> for FieldIndex<S> {
+    fn eq(&self, other: &usize) -> bool {
+        self.index == *other
+    }
+}
+
+impl<S> PartialOrd for FieldIndex<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for FieldIndex<S> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.index.cmp(&other.index)
+    }
+}
+
+impl<S> Hash for FieldIndex<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.index.hash(state)
+    }
+}
+
+impl<S> fmt::Debug for FieldIndex<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("FieldIndex").field(&self.index).finish()
+    }
+}
+
+impl<S> From<FieldIndex<S>> for usize {
+    fn from(index: FieldIndex<S>) -> Self {
+        index.index
+    }
+}
+
+/// The error returned when converting from a collection whose length doesn't
+/// match the number of fields
+///
+/// ```
+/// # use array_as_struct::{array_as_struct_doctest as array_as_struct, LengthMismatch};
+/// # mod _hider{
+/// use array_as_struct::{array_as_struct, LengthMismatch};
+/// # }
+///
+/// #[array_as_struct]
+/// #[derive(Debug)]
+/// pub struct Foo {
+///     bar: u32,
+///     baz: u32,
+/// }
+///
+/// let err = Foo::try_from(&[1, 2, 3][..]).unwrap_err();
+/// assert_eq!(err, LengthMismatch { expected: 2, actual: 3 });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LengthMismatch {
+    /// The number of fields
+    pub expected: usize,
+    /// The length of the collection
+    pub actual: usize,
+}
+
+impl fmt::Display for LengthMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected {} elements, found {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl core::error::Error for LengthMismatch {}
+
+/// Floating-point field types, for the geometric methods generated by the
+/// `ops` option, like `length` and `normalize`
+///
+/// This is implemented for `f32` and `f64` when a square root is available:
+/// with the `std` feature, or in `no_std` crates with the `libm` feature, which
+/// uses the [`libm`](https://docs.rs/libm) crate. Without either, `length` and
+/// `normalize` can't be called on them, but `length_squared` still can.
+///
+/// ```
+/// # use array_as_struct::array_as_struct_doctest as array_as_struct;
+/// # mod _hider{
+/// use array_as_struct::array_as_struct;
+/// # }
+///
+/// #[array_as_struct(ops)]
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// pub struct Vec2 {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// let v = Vec2([3.0, 4.0]);
+/// assert_eq!(v.length_squared(), 25.0);
+/// # #[cfg(any(feature = "std", feature = "libm"))]
+/// assert_eq!(v.length(), 5.0);
+/// # #[cfg(any(feature = "std", feature = "libm"))]
+/// assert_eq!(v.normalize(), Vec2([0.6, 0.8]));
+/// ```
+pub trait Float:
+    Copy
+    + PartialEq
+    + core::ops::Add<Output = Self>
+    + core::ops::Mul<Output = Self>
+    + core::ops::Div<Output = Self>
+{
+    /// Zero, the length of a vector whose fields are all zero
+    const ZERO: Self;
+
+    /// The square root
+    fn sqrt(self) -> Self;
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl Float for f32 {
+    const ZERO: Self = 0.0;
+
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        #[cfg(feature = "std")]
+        return f32::sqrt(self);
+        #[cfg(not(feature = "std"))]
+        return libm::sqrtf(self);
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl Float for f64 {
+    const ZERO: Self = 0.0;
+
+    #[inline(always)]
+    fn sqrt(self) -> Self {
+        #[cfg(feature = "std")]
+        return f64::sqrt(self);
+        #[cfg(not(feature = "std"))]
+        return libm::sqrt(self);
+    }
 }
 
 pub use array_as_struct_derive::array_as_struct;
 
 #[doc(hidden)]
 pub use array_as_struct_derive::array_as_struct_doctest;
+
+#[cfg(doctest)]
+mod compile_fail;
+
+#[doc(hidden)]
+pub mod __private {
+    #[cfg(feature = "alloc")]
+    pub extern crate alloc;
+    #[cfg(feature = "bytemuck")]
+    pub use bytemuck;
+    #[cfg(feature = "serde")]
+    pub use serde;
+}
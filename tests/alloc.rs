@@ -0,0 +1,31 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::BTreeMap;
+
+use array_as_struct::{array_as_struct, LengthMismatch};
+
+#[array_as_struct]
+pub struct Stats {
+    min: i64,
+    max: i64,
+    mean: i64,
+}
+
+#[test]
+fn to_map() {
+    let map = Stats([-3, 7, 2]).to_map();
+
+    assert_eq!(map, BTreeMap::from([("min", -3), ("max", 7), ("mean", 2)]));
+}
+
+#[test]
+fn try_from_vec() {
+    assert_eq!(Stats::try_from(vec![-3, 7, 2]).map(|s| s.0), Ok([-3, 7, 2]));
+    assert_eq!(
+        Stats::try_from(vec![1, 2]).map(|s| s.0),
+        Err(LengthMismatch {
+            expected: 3,
+            actual: 2
+        })
+    );
+}
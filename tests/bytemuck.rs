@@ -0,0 +1,56 @@
+#![cfg(feature = "bytemuck")]
+
+use array_as_struct::array_as_struct;
+
+#[array_as_struct(bytemuck)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vertex {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[test]
+fn cast() {
+    let vertices = [Vertex([1.0, 2.0, 3.0]), Vertex([4.0, 5.0, 6.0])];
+
+    let floats: &[f32] = bytemuck::cast_slice(&vertices);
+    assert_eq!(floats, [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    assert_eq!(bytemuck::cast_slice::<f32, Vertex>(floats), vertices);
+    assert_eq!(<Vertex as bytemuck::Zeroable>::zeroed(), Vertex([0.0; 3]));
+}
+
+#[array_as_struct(bytemuck, repr = "C")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+}
+
+#[array_as_struct(bytemuck)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Uv {
+    u: f32,
+    v: f32,
+}
+
+#[test]
+fn repr_c() {
+    assert_eq!(
+        bytemuck::cast::<u32, Rgba>(u32::from_ne_bytes([1, 2, 3, 4])),
+        Rgba([1, 2, 3, 4])
+    );
+    assert_eq!(bytemuck::cast::<[f32; 2], Uv>([0.5, 1.0]), Uv([0.5, 1.0]));
+}
+
+#[test]
+fn from_array_ref() {
+    let mut floats = [1.0, 2.0, 3.0];
+
+    assert_eq!(Vertex::from_array_ref(&floats), &Vertex([1.0, 2.0, 3.0]));
+    *Vertex::from_array_mut(&mut floats).y_mut() = 5.0;
+    assert_eq!(floats, [1.0, 5.0, 3.0]);
+}
@@ -0,0 +1,21 @@
+#![deny(missing_docs)]
+//! Checks that documenting the original declaration leaves the generated
+//! struct fully documented
+
+use array_as_struct::array_as_struct;
+
+/// A point on the screen
+#[array_as_struct]
+#[derive(Debug, Clone, Copy, PartialEq)]
+/// in pixels
+pub struct Pixel {
+    /// The column
+    x: u16,
+    /// The row
+    y: u16,
+}
+
+#[test]
+fn documented() {
+    assert_eq!(Pixel::new(3, 4), Pixel([3, 4]));
+}
@@ -0,0 +1,79 @@
+#![cfg(feature = "serde")]
+
+use array_as_struct::array_as_struct;
+
+#[array_as_struct(serde)]
+#[derive(Debug, PartialEq)]
+pub struct Color {
+    r: u8,
+    g: u8,
+    b: u8,
+}
+
+#[test]
+fn map() {
+    let color = Color([247, 76, 0]);
+
+    let json = serde_json::to_string(&color).unwrap();
+    assert_eq!(json, r#"{"r":247,"g":76,"b":0}"#);
+    assert_eq!(serde_json::from_str::<Color>(&json).unwrap(), color);
+    assert_eq!(
+        serde_json::from_str::<Color>(r#"{"b":0,"a":1,"g":76,"r":247}"#).unwrap(),
+        color,
+    );
+    assert_eq!(serde_json::from_str::<Color>("[247,76,0]").unwrap(), color);
+
+    let err = serde_json::from_str::<Color>(r#"{"r":247,"g":76}"#).unwrap_err();
+    assert!(err.to_string().starts_with("missing field `b`"));
+    let err = serde_json::from_str::<Color>(r#"{"r":247,"g":76,"r":0}"#).unwrap_err();
+    assert!(err.to_string().starts_with("duplicate field `r`"));
+}
+
+#[array_as_struct(serde)]
+#[derive(Debug, PartialEq)]
+pub struct Tint {
+    #[serde(rename = "red")]
+    r: u8,
+    g: u8,
+    #[serde(rename = "blue")]
+    b: u8,
+}
+
+#[test]
+fn rename() {
+    let tint = Tint([1, 2, 3]);
+
+    let json = serde_json::to_string(&tint).unwrap();
+    assert_eq!(json, r#"{"red":1,"g":2,"blue":3}"#);
+    assert_eq!(serde_json::from_str::<Tint>(&json).unwrap(), tint);
+    assert!(serde_json::from_str::<Tint>(r#"{"r":1,"g":2,"b":3}"#).is_err());
+
+    let err = serde_json::from_str::<Tint>(r#"{"red":1,"g":2}"#).unwrap_err();
+    assert!(err.to_string().starts_with("missing field `blue`"));
+    assert_eq!(Tint::NAMES, ["r", "g", "b"]);
+}
+
+#[array_as_struct(serde = "array")]
+#[derive(Debug, PartialEq)]
+pub struct Position {
+    x: i32,
+    y: i32,
+}
+
+#[test]
+fn array() {
+    let position = Position([3, -4]);
+
+    let json = serde_json::to_string(&position).unwrap();
+    assert_eq!(json, "[3,-4]");
+    assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), position);
+
+    let err = serde_json::from_str::<Position>("[3]").unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("invalid length 1, expected an array of length 2 for struct Position"));
+    let err = serde_json::from_str::<Position>("[3,-4,5]").unwrap_err();
+    assert!(err
+        .to_string()
+        .starts_with("invalid length 3, expected an array of length 2 for struct Position"));
+}
@@ -1,4 +1,8 @@
-use array_as_struct::{array_as_struct, ArrayStruct};
+#![allow(clippy::disallowed_names)]
+
+use std::cell::RefCell;
+
+use array_as_struct::{array_as_struct, ArrayStruct, ArrayStructExt, LengthMismatch};
 
 #[array_as_struct]
 #[derive(Clone)]
@@ -16,9 +20,1208 @@ fn main() {
 
     assert_eq!(<Foo as ArrayStruct>::Index::bar(), 0);
     assert_eq!(<Foo as ArrayStruct>::Index::baz(), 1);
+    assert_eq!(<Foo as ArrayStruct>::FIELD_COUNT, 2);
+    assert_eq!(f.len(), 2);
+    assert!(!f.is_empty());
     assert_eq!(f.0, [10, 15]);
     f[<Foo as ArrayStruct>::Index::bar()] = 2;
     assert_eq!(*f.refs().bar, 2);
     *f.muts().baz = 12;
     assert_eq!(*f.refs().baz, 12);
 }
+
+#[test]
+fn eq_value() {
+    type Value = <Foo as ArrayStruct>::Value;
+
+    let f = Foo([10, 15]);
+    assert!(f == Value { bar: 10, baz: 15 });
+    assert!(f != Value { bar: 10, baz: 16 });
+    assert!(Value { bar: 10, baz: 15 } == f);
+}
+
+#[test]
+fn iter() {
+    let mut f = Foo([10, 15]);
+
+    assert!(f.iter().eq(&[10, 15]));
+    for x in f.iter_mut() {
+        *x += 1;
+    }
+    assert_eq!(f.0, [11, 16]);
+}
+
+/// Records its id in a shared log when dropped
+pub struct Tracked<'a>(u8, &'a RefCell<Vec<u8>>);
+
+impl Drop for Tracked<'_> {
+    fn drop(&mut self) {
+        self.1.borrow_mut().push(self.0);
+    }
+}
+
+#[array_as_struct]
+pub struct Tracks<'a> {
+    first: Tracked<'a>,
+    second: Tracked<'a>,
+    third: Tracked<'a>,
+}
+
+#[test]
+fn into_values() {
+    let log = RefCell::new(vec![]);
+    let tracks = Tracks([Tracked(1, &log), Tracked(2, &log), Tracked(3, &log)]);
+    let tracks = Tracks::from_val(tracks.val());
+
+    let mut values = tracks.into_values();
+    assert_eq!(values.next().map(|t| t.0), Some(1));
+    assert_eq!(*log.borrow(), [1]);
+    drop(values);
+    assert_eq!(*log.borrow(), [1, 2, 3]);
+}
+
+#[test]
+fn copied() {
+    const F: Foo = Foo([10, 15]);
+    const BAZ: u32 = F.baz_copied();
+
+    assert_eq!(F.bar_copied() + BAZ, 25);
+}
+
+#[test]
+fn iterate_refs() {
+    let mut f = Foo([10, 15]);
+
+    for x in f.muts() {
+        *x *= 2;
+    }
+    assert!(f.refs().into_iter().eq(&[20, 30]));
+    assert_eq!(Nothing([]).refs().into_iter().count(), 0);
+}
+
+#[test]
+fn indexed_refs() {
+    let f = Foo([10, 15]);
+
+    assert!(f.indexed_refs().eq([(0, &10), (1, &15)]));
+}
+
+#[test]
+fn map_in_place() {
+    let mut f = Foo([10, 300]);
+
+    f.map_in_place(|x| *x = (*x).clamp(0, 255));
+    assert_eq!(f.0, [10, 255]);
+}
+
+#[test]
+fn fold() {
+    let f = Foo([10, 15]);
+
+    assert_eq!(f.fold(1, |acc, &x| acc * 31 + x), 1286);
+    assert_eq!(f.reduce(u32::max), Some(15));
+    assert_eq!(Nothing([]).reduce(u8::max), None);
+}
+
+#[test]
+fn ext_generic() {
+    fn weighted<S: ArrayStructExt<Element = u32>>(s: &S) -> u32 {
+        s.fold(0, |acc, &x| acc * 2 + x)
+    }
+
+    fn double<S: ArrayStructExt<Element = u32>>(s: &mut S) {
+        s.iter_mut().for_each(|x| *x *= 2);
+    }
+
+    assert_eq!(weighted(&Foo([10, 15])), 35);
+    assert_eq!(weighted(&Size([2, 3])), 7);
+    let mut f = Foo([10, 15]);
+    double(&mut f);
+    assert_eq!(f.0, [20, 30]);
+}
+
+#[test]
+fn all_any() {
+    let f = Foo([10, 15]);
+
+    assert!(f.all(|&x| x > 0));
+    assert!(!f.all(|&x| x > 10));
+    assert!(f.any(|&x| x > 10));
+    assert!(!f.any(|&x| x > 15));
+}
+
+#[test]
+fn contains() {
+    let f = Foo([10, 15]);
+
+    assert!(f.contains(&15));
+    assert!(!f.contains(&12));
+}
+
+#[test]
+fn into_iter() {
+    let mut f = Foo([10, 15]);
+
+    for x in &mut f {
+        *x *= 2;
+    }
+    assert!((&f).into_iter().eq(&[20, 30]));
+    assert!(f.into_iter().eq([20, 30]));
+}
+
+/// Cloneable, but deliberately not `Copy`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ticket(u32);
+
+#[array_as_struct]
+pub struct Queue {
+    front: Ticket,
+    back: Ticket,
+}
+
+#[test]
+fn clone_array() {
+    let queue = Queue([Ticket(1), Ticket(2)]);
+
+    assert_eq!(queue.clone_array(), [Ticket(1), Ticket(2)]);
+    assert_eq!(queue.front(), &Ticket(1));
+}
+
+#[test]
+fn copy_array() {
+    const F: Foo = Foo([1, 2]);
+    const ARRAY: [u32; 2] = F.copy_array();
+
+    assert_eq!(ARRAY, [1, 2]);
+    assert_eq!(F.to_array_ref(), &[1, 2]);
+}
+
+#[test]
+fn slice() {
+    const F: Foo = Foo([1, 2]);
+    const LEN: usize = F.as_slice().len();
+    assert_eq!(LEN, 2);
+
+    let mut f = F;
+    f.as_mut_slice().reverse();
+    assert_eq!(f.as_slice(), &[2, 1]);
+}
+
+#[test]
+fn chunks_windows() {
+    let a = Vec3([1, 2, 3]);
+
+    assert!(a.chunks(2).eq([&[1, 2][..], &[3]]));
+    assert!(a.windows(2).eq([[1, 2], [2, 3]]));
+}
+
+#[test]
+fn split_first_last() {
+    let mut a = Vec3([1, 2, 3]);
+
+    assert_eq!(a.split_first(), Some((&1, &[2, 3][..])));
+    assert_eq!(a.split_last(), Some((&3, &[1, 2][..])));
+    if let Some((first, rest)) = a.split_first_mut() {
+        *first += rest.iter().sum::<i32>();
+    }
+    if let Some((last, _)) = a.split_last_mut() {
+        *last = 0;
+    }
+    assert_eq!(a, Vec3([6, 2, 0]));
+    assert_eq!(Nothing([]).split_first(), None);
+}
+
+#[test]
+fn each_ref() {
+    let mut f = Foo([1, 2]);
+    let [bar, baz] = f.each_mut();
+    core::mem::swap(bar, baz);
+    assert_eq!(f.each_ref(), [&2, &1]);
+}
+
+#[test]
+fn index() {
+    let mut f = Foo([1, 2]);
+    f[0] = 3;
+    f[1..].copy_from_slice(&[4]);
+    assert_eq!(f[..], [3, 4]);
+    assert_eq!(f[..=0], [3]);
+    f[<Foo as ArrayStruct>::Index::baz()] = 5;
+    assert_eq!(f[<Foo as ArrayStruct>::Index::baz()], 5);
+}
+
+#[test]
+fn from_iter() {
+    let f: Foo = (1..).collect();
+    assert_eq!(f.0, [1, 2]);
+    assert_eq!(Foo::try_from_iter([3, 4]).map(|f| f.0), Ok([3, 4]));
+    assert_eq!(
+        Foo::try_from_iter([3]).map(|f| f.0),
+        Err(LengthMismatch {
+            expected: 2,
+            actual: 1
+        })
+    );
+}
+
+#[test]
+#[should_panic = "not enough items to collect into `Foo`: expected 2 elements, found 1"]
+fn from_iter_too_short() {
+    let _: Foo = [1].into_iter().collect();
+}
+
+#[test]
+fn eq_array() {
+    let f = Foo([10, 15]);
+    assert!(f == [10, 15]);
+    assert!([10, 15] == f);
+    assert!(f != [15, 10]);
+}
+
+#[test]
+fn try_from_slice() {
+    let buf = [1, 2, 3];
+    assert_eq!(Foo::try_from(&buf[1..]).map(|f| f.0), Ok([2, 3]));
+    assert_eq!(
+        Foo::try_from(&buf[..]).map(|f| f.0),
+        Err(LengthMismatch {
+            expected: 2,
+            actual: 3
+        })
+    );
+}
+
+#[test]
+fn const_muts() {
+    const F: Foo = {
+        let mut f = Foo([10, 15]);
+        *f.muts().bar = 20;
+        *f.baz_mut() += 5;
+        f
+    };
+
+    assert_eq!(F.0, [20, 20]);
+}
+
+#[test]
+fn accessors() {
+    let mut f = Foo([10, 15]);
+
+    assert_eq!(*f.bar(), 10);
+    *f.baz_mut() = 20;
+    assert_eq!(*f.baz(), 20);
+    f.set_bar(30);
+    assert_eq!(*f.bar(), 30);
+}
+
+#[test]
+fn new() {
+    static F: Foo = Foo::new(10, 15);
+
+    assert_eq!(F.0, [10, 15]);
+}
+
+#[test]
+fn const_context() {
+    const F: Foo = Foo::new(10, 15);
+    const BAR: u32 = *F.refs().bar;
+    const BAZ: u32 = *F.baz();
+    const ARRAY: [u32; 2] = *F.to_array_ref();
+    const fn bumped(mut f: Foo) -> Foo {
+        *f.bar_mut() += 1;
+        *f.muts().baz += 1;
+        f
+    }
+    const G: Foo = bumped(Foo([1, 2]));
+
+    assert_eq!((BAR, BAZ), (10, 15));
+    assert_eq!(ARRAY, [10, 15]);
+    assert_eq!(G.0, [2, 3]);
+}
+
+#[test]
+fn from_fn() {
+    let f = Foo::from_fn(|i| 10 * i as u32);
+
+    assert_eq!(f.0, [0, 10]);
+}
+
+#[test]
+fn from_array_into() {
+    let f = Foo::from_array_into([3u8, 4]);
+
+    assert_eq!(f.0, [3, 4]);
+}
+
+#[test]
+fn splat() {
+    let f = Foo::splat(7);
+
+    assert_eq!(f.0, [7, 7]);
+}
+
+#[array_as_struct]
+#[derive(Debug, Default, PartialEq)]
+pub struct Channels {
+    c0: u8,
+    c1: u8,
+    c2: u8,
+    c3: u8,
+    c4: u8,
+    c5: u8,
+    c6: u8,
+    c7: u8,
+    c8: u8,
+    c9: u8,
+    c10: u8,
+    c11: u8,
+    c12: u8,
+    c13: u8,
+    c14: u8,
+    c15: u8,
+    c16: u8,
+    c17: u8,
+    c18: u8,
+    c19: u8,
+    c20: u8,
+    c21: u8,
+    c22: u8,
+    c23: u8,
+    c24: u8,
+    c25: u8,
+    c26: u8,
+    c27: u8,
+    c28: u8,
+    c29: u8,
+    c30: u8,
+    c31: u8,
+    c32: u8,
+}
+
+#[test]
+fn default() {
+    assert_eq!(Foo::default().0, [0, 0]);
+    // `[u8; 33]` isn't `Default`, so this relies on the generated impl
+    assert_eq!(Channels::default(), Channels([0; 33]));
+}
+
+#[array_as_struct(ops, with)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Vec3 {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+#[test]
+fn ops() {
+    let a = Vec3([1, 2, 3]);
+    let b = Vec3([4, 6, 9]);
+
+    assert_eq!(a + b, Vec3([5, 8, 12]));
+    assert_eq!(b - a, Vec3([3, 4, 6]));
+    assert_eq!(a * b, Vec3([4, 12, 27]));
+    assert_eq!(b / a, Vec3([4, 3, 3]));
+    assert_eq!(a + b - a, b);
+}
+
+#[array_as_struct(bitops)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Mask {
+    read: u8,
+    write: u8,
+}
+
+#[test]
+fn bitops() {
+    let a = Mask([0b1100, 0b1010]);
+    let b = Mask([0b1010, 0b0110]);
+
+    assert_eq!(a & b, Mask([0b1000, 0b0010]));
+    assert_eq!(a | b, Mask([0b1110, 0b1110]));
+    assert_eq!(a ^ b, Mask([0b0110, 0b1100]));
+    assert_eq!(!a, Mask([!0b1100, !0b1010]));
+
+    let mut c = a;
+    c &= b;
+    assert_eq!(c, a & b);
+    c |= a;
+    assert_eq!(c, a);
+    c ^= a;
+    assert_eq!(c, Mask([0, 0]));
+}
+
+#[test]
+fn shifts() {
+    let a = Mask([0b0011, 0b0110]);
+
+    assert_eq!(a << 2, Mask([0b1100, 0b11000]));
+    assert_eq!(a >> 1u8, Mask([0b0001, 0b0011]));
+
+    let mut b = a;
+    b <<= 4i64;
+    assert_eq!(b, Mask([0b0011_0000, 0b0110_0000]));
+    b >>= 4;
+    assert_eq!(b, a);
+}
+
+#[test]
+fn neg() {
+    let a = Vec3([1, -2, 0]);
+
+    assert_eq!(-a, Vec3([-1, 2, 0]));
+    assert_eq!(-(-a), a);
+    assert_eq!(a + -a, Vec3::splat(0));
+}
+
+#[test]
+fn min_max() {
+    let a = Vec3([4, 1, 9]);
+    let f = Vec3f([0.5, -2.0, 1.5]);
+
+    assert_eq!((a.min_element(), a.max_element()), (Some(&1), Some(&9)));
+    assert_eq!(f.min_element_by(f64::total_cmp), Some(&-2.0));
+    assert_eq!(f.max_element_by(f64::total_cmp), Some(&1.5));
+    assert_eq!(Nothing([]).max_element(), None);
+}
+
+#[array_as_struct]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    min: f32,
+    max: f32,
+}
+
+#[test]
+fn min_max_fields() {
+    let mut b = Bounds::new(-1.0, 1.0);
+    *b.max_mut() = 2.0;
+
+    assert_eq!((b.min(), b.max()), (&-1.0, &2.0));
+    assert_eq!(b.max_element_by(f32::total_cmp), Some(&2.0));
+}
+
+#[test]
+fn dot() {
+    let a = Vec3([1, 2, 3]);
+    let b = Vec3([4, 6, 9]);
+
+    assert_eq!(a.dot(&b), 43);
+    assert_eq!(a.dot(&a), 14);
+}
+
+#[array_as_struct(ops)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dir {
+    x: f64,
+    y: f64,
+}
+
+#[test]
+fn normalize() {
+    let v = Dir([3.0, 4.0]);
+
+    assert_eq!(v.length_squared(), 25.0);
+    assert_eq!(v.length(), 5.0);
+    assert_eq!(v.normalize(), Dir([0.6, 0.8]));
+    assert_eq!(Dir([0.0, 0.0]).normalize(), Dir([0.0, 0.0]));
+    assert_eq!(Vec3([1, 2, 3]).length_squared(), 14);
+}
+
+#[array_as_struct(ops)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dir32 {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[test]
+fn float_impls() {
+    use array_as_struct::Float;
+
+    assert_eq!(<f32 as Float>::sqrt(2.0), 2f32.sqrt());
+    assert_eq!(<f64 as Float>::sqrt(2.0), 2f64.sqrt());
+    assert_eq!(<f32 as Float>::ZERO, 0.0);
+
+    let v = Dir32([2.0, 3.0, 6.0]);
+    assert_eq!(v.length(), 7.0);
+    assert!((v.normalize().length() - 1.0).abs() < f32::EPSILON);
+}
+
+#[test]
+fn clamp() {
+    let a = Vec3([-4, 5, 20]);
+
+    assert_eq!(a.clamp_scalar(0, 10), Vec3([0, 5, 10]));
+    assert_eq!(a.clamp(Vec3([0, 6, 0]), Vec3([1, 8, 30])), Vec3([0, 6, 20]));
+}
+
+#[test]
+fn scalar_ops() {
+    let mut a = Vec3([2, 4, 6]);
+
+    assert_eq!(a + 1, Vec3([3, 5, 7]));
+    assert_eq!(a - 1, Vec3([1, 3, 5]));
+    assert_eq!(a * 3, Vec3([6, 12, 18]));
+    assert_eq!(a / 2, Vec3([1, 2, 3]));
+
+    a *= 2;
+    assert_eq!(a, Vec3([4, 8, 12]));
+    a /= 4;
+    a += 1;
+    a -= 2;
+    assert_eq!(a, Vec3([0, 1, 2]));
+}
+
+#[array_as_struct(ord)]
+#[derive(Debug, PartialEq)]
+pub struct Vec3f {
+    x: f64,
+    y: f64,
+    z: f64,
+}
+
+#[test]
+fn map() {
+    let a = Vec3([1, 2, 3]);
+
+    assert_eq!(a.map_array(|x| x * 2), [2, 4, 6]);
+    let b: Vec3f = a.map_fields(|x| x as f64 / 2.0);
+    assert_eq!(b, Vec3f([0.5, 1.0, 1.5]));
+    // `map` is still the array's, reached through `Deref`
+    assert_eq!(a.map(|x| x + 1), [2, 3, 4]);
+}
+
+#[test]
+fn map_array_with_index() {
+    let a = Vec3([1, 2, 3]);
+
+    assert_eq!(
+        a.map_array_with_index(|i, x| x * 10 + i as i32),
+        [10, 21, 32]
+    );
+}
+
+#[array_as_struct(swizzle)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgba {
+    r: u8,
+    g: u8,
+    b: u8,
+    a: u8,
+}
+
+#[test]
+fn swizzle() {
+    let rgba = Rgba([1, 2, 3, 4]);
+
+    assert_eq!(rgba.swizzle::<2, 1, 0, 3>(), Rgba([3, 2, 1, 4]));
+    assert_eq!(rgba.swizzle::<0, 0, 0, 3>(), Rgba([1, 1, 1, 4]));
+}
+
+#[test]
+fn with() {
+    let a = Vec3([1, 2, 3]).with_x(4).with_z(6);
+
+    assert_eq!(a, Vec3([4, 2, 6]));
+}
+
+#[array_as_struct]
+#[derive(Debug, PartialEq)]
+pub struct Vec3u8 {
+    x: u8,
+    y: u8,
+    z: u8,
+}
+
+#[test]
+fn try_map() {
+    let mut calls = 0;
+    let mut to_u8 = |x: i32| {
+        calls += 1;
+        u8::try_from(x)
+    };
+
+    assert_eq!(Vec3([1, 2, 3]).try_map_array(&mut to_u8), Ok([1, 2, 3]));
+    assert!(Vec3([1, -2, 3]).try_map_array(&mut to_u8).is_err());
+    assert_eq!(calls, 5);
+    assert_eq!(Vec3([1, 2, 3]).try_map(u8::try_from), Ok(Vec3u8([1, 2, 3])));
+    assert!(Vec3([1, 2, 300])
+        .try_map::<_, _, Vec3u8>(u8::try_from)
+        .is_err());
+}
+
+#[test]
+fn sum() {
+    assert_eq!(Vec3([1, 2, 3]).sum::<i32>(), 6);
+    assert_eq!(Vec3([1, 2, 3]).product::<i32>(), 6);
+    assert_eq!(Vec3f([0.5, 1.0, 1.5]).sum::<f64>(), 3.0);
+    assert_eq!(Vec3f([0.5, 1.0, 1.5]).product::<f64>(), 0.75);
+}
+
+#[test]
+fn zip() {
+    let a = Vec3([1, 2, 3]);
+    let b = Vec3([4, 5, 6]);
+
+    assert!(a.zip(&b).eq([(&1, &4), (&2, &5), (&3, &6)]));
+    assert!(a.zip_values(b).eq([(1, 4), (2, 5), (3, 6)]));
+}
+
+#[test]
+fn swap() {
+    let mut a = Vec3([1, 2, 3]);
+
+    a.swap(0, 2);
+    assert_eq!(a, Vec3([3, 2, 1]));
+}
+
+#[test]
+#[should_panic]
+fn swap_out_of_bounds() {
+    Vec3([1, 2, 3]).swap(0, 3);
+}
+
+#[test]
+fn reverse() {
+    let mut a = Vec3([1, 2, 3]);
+
+    a.reverse();
+    assert_eq!(a, Vec3([3, 2, 1]));
+    assert_eq!(a.reversed(), Vec3([1, 2, 3]));
+}
+
+#[test]
+fn fill() {
+    let mut queue = Queue([Ticket(1), Ticket(2)]);
+
+    queue.fill(Ticket(0));
+    assert_eq!(queue.clone_array(), [Ticket(0), Ticket(0)]);
+}
+
+#[test]
+fn tuples() {
+    let a = Vec3::from_tuple((1, 2, 3));
+
+    assert_eq!(a, Vec3([1, 2, 3]));
+    assert_eq!(a.into_tuple(), (1, 2, 3));
+}
+
+#[test]
+fn rotate() {
+    let a = Vec3([1, 2, 3]);
+
+    assert_eq!(a.rotated_left(1), Vec3([2, 3, 1]));
+    assert_eq!(a.rotated_left(4), Vec3([2, 3, 1]));
+    assert_eq!(a.rotated_right(1), Vec3([3, 1, 2]));
+    assert_eq!(a.rotated_right(3), a);
+    assert_eq!(Nothing([]).rotated_left(1), Nothing([]));
+
+    // The slice methods, reached through `DerefMut`, still rotate in place
+    let mut b = a;
+    b.rotate_left(1);
+    assert_eq!(b, Vec3([2, 3, 1]));
+    b.rotate_right(2);
+    assert_eq!(b, Vec3([3, 1, 2]));
+}
+
+#[test]
+fn concat_array() {
+    let a = Vec3([1, 2, 3]);
+
+    let b: [i32; 5] = a.concat_array([4, 5]);
+    assert_eq!(b, [1, 2, 3, 4, 5]);
+    assert_eq!(a.concat_array::<0, 3>([]), [1, 2, 3]);
+}
+
+#[test]
+fn split_array() {
+    let a = Vec3([1, 2, 3]);
+
+    let (head, tail): ([i32; 1], _) = a.split_array();
+    assert_eq!((head, tail), ([1], [2, 3]));
+    assert_eq!(a.split_array::<2, 1>(), ([1, 2], [3]));
+    assert_eq!(a.split_array::<3, 0>(), ([1, 2, 3], []));
+    // `split_at` is still the slice's, reached through `Deref`
+    assert_eq!(a.split_at(1), (&[1][..], &[2, 3][..]));
+}
+
+#[array_as_struct]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affine {
+    xx: i32,
+    xy: i32,
+    xz: i32,
+    yx: i32,
+    yy: i32,
+    yz: i32,
+}
+
+#[test]
+fn to_matrix() {
+    let a = Affine([1, 2, 3, 4, 5, 6]);
+
+    assert_eq!(a.to_matrix::<2, 3>(), [[1, 2, 3], [4, 5, 6]]);
+    assert_eq!(a.to_matrix::<3, 2>(), [[1, 2], [3, 4], [5, 6]]);
+    let column: [[i32; 1]; 6] = a.to_matrix();
+    assert_eq!(column[5], [6]);
+    assert_eq!(Nothing([]).to_matrix::<0, 4>(), [[0u8; 4]; 0]);
+}
+
+#[test]
+fn project() {
+    type Index = <Vec3 as ArrayStruct>::Index;
+    let a = Vec3([1, 2, 3]);
+
+    assert_eq!(a.project([Index::z().get(), Index::x().get()]), [3, 1]);
+    assert_eq!(a.project([1, 1, 1, 1]), [2; 4]);
+    assert_eq!(a.project([]), []);
+}
+
+#[test]
+#[should_panic = "field index 3 is out of range for `Vec3` with 3 fields"]
+fn project_out_of_bounds() {
+    Vec3([1, 2, 3]).project([0, 3]);
+}
+
+#[test]
+fn get() {
+    let mut f = Foo([10, 15]);
+
+    assert_eq!(f.get(1), Some(&15));
+    assert_eq!(f.get(2), None);
+    if let Some(x) = f.get_mut(0) {
+        *x = 5;
+    }
+    assert_eq!(f.get_mut(2), None);
+    assert_eq!(f.0, [5, 15]);
+
+    assert_eq!(f.get(0..1), Some(&[5][..]));
+    assert_eq!(f.get(1..), Some(&[15][..]));
+    assert_eq!(f.get(1..3), None);
+    if let Some(rest) = f.get_mut(..) {
+        rest[1] = 20;
+    }
+    assert_eq!(f.0, [5, 20]);
+}
+
+#[test]
+fn names() {
+    assert_eq!(Foo::NAMES, ["bar", "baz"]);
+    assert_eq!(Foo::name_of(1), Some("baz"));
+    assert_eq!(Foo::name_of(2), None);
+    assert_eq!(Foo::index_of("baz"), Some(1));
+    assert_eq!(Foo::index_of("qux"), None);
+}
+
+#[test]
+fn field_offsets() {
+    assert_eq!(Foo::FIELD_OFFSETS, [0, 4]);
+    assert_eq!(Vec3f::FIELD_OFFSETS, [0, 8, 16]);
+    assert_eq!(Nothing::FIELD_OFFSETS, []);
+}
+
+#[test]
+fn find_name() {
+    let f = Foo([10, 0]);
+
+    assert_eq!(f.find_name(|&x| x == 0), Some("baz"));
+    assert_eq!(f.find_name(|&x| x > 10), None);
+}
+
+#[test]
+fn index_by_name() {
+    let mut f = Foo([10, 15]);
+
+    f["baz"] += 1;
+    assert_eq!(f["baz"], 16);
+    assert_eq!(f.get_by_name("bar"), Some(&10));
+    assert_eq!(f.get_by_name("qux"), None);
+    *f.get_by_name_mut("bar").unwrap() = 11;
+    assert_eq!(f.0, [11, 16]);
+}
+
+#[test]
+#[should_panic = "`Foo` has no field named `qux`"]
+fn index_by_unknown_name() {
+    let _ = Foo([10, 15])["qux"];
+}
+
+#[test]
+fn named_refs() {
+    let mut f = Foo([10, 15]);
+
+    for (name, value) in f.named_muts() {
+        *value += name.len() as u32;
+    }
+    assert!(f.named_refs().eq([("bar", &13), ("baz", &18)]));
+}
+
+#[test]
+fn enumerate_named_mut() {
+    let mut f = Foo([10, 15]);
+
+    for (name, index, value) in f.enumerate_named_mut() {
+        *value = (name.len() + index) as u32;
+    }
+    assert_eq!(f.0, [3, 4]);
+}
+
+#[array_as_struct(value = "TempsData", index = "TempsIdx")]
+pub struct Temps {
+    low: f32,
+    high: f32,
+}
+
+#[test]
+fn helper_names() {
+    let value_name = core::any::type_name::<<Temps as ArrayStruct>::Value>();
+    let index_name = core::any::type_name::<<Temps as ArrayStruct>::Index>();
+    let refs_name = core::any::type_name::<<Temps as ArrayStruct>::Refs<'static>>();
+
+    assert!(value_name.ends_with("::TempsData"), "{value_name}");
+    assert!(index_name.ends_with("::TempsIdx"), "{index_name}");
+    assert!(refs_name.contains("::Refs<"), "{refs_name}");
+}
+
+mod encapsulated {
+    use array_as_struct::array_as_struct;
+
+    #[array_as_struct(private_array)]
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Interval {
+        start: u32,
+        end: u32,
+    }
+}
+
+#[test]
+fn private_array() {
+    use encapsulated::Interval;
+
+    let mut i = Interval::new(1, 5);
+    *i.end_mut() = 7;
+    assert_eq!(i.to_array(), [1, 7]);
+    assert_eq!(Interval::from_array([1, 7]), i);
+}
+
+#[deny(private_interfaces, private_bounds, unreachable_pub)]
+mod restricted {
+    use array_as_struct::array_as_struct;
+
+    #[array_as_struct(expose, debug)]
+    #[derive(Clone, Copy, PartialEq)]
+    pub(crate) struct Extent {
+        pub(crate) width: u16,
+        pub(crate) height: u16,
+    }
+}
+
+#[test]
+fn restricted_visibility() {
+    use restricted::{Extent, ExtentIndex, ExtentValue};
+
+    let mut e = Extent::new(3, 4);
+    e[ExtentIndex::height()] = 5;
+    assert_eq!(e.to_array(), [3, 5]);
+    let v = ExtentValue {
+        width: 1,
+        height: 2,
+    };
+    assert_eq!(v.to_array_struct().to_array(), [1, 2]);
+}
+
+#[array_as_struct(value_derives(Debug, PartialEq))]
+#[derive(Clone, Copy)]
+pub struct Range {
+    start: f32,
+    end: f32,
+}
+
+#[test]
+fn value_derives() {
+    type Value = <Range as ArrayStruct>::Value;
+
+    let r = Range([0.0, 1.5]);
+    assert_eq!(
+        r.val(),
+        Value {
+            start: 0.0,
+            end: 1.5
+        }
+    );
+    assert_eq!(format!("{:?}", r.val()), "Value { start: 0.0, end: 1.5 }");
+}
+
+#[array_as_struct(expose)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Size {
+    width: u32,
+    height: u32,
+}
+
+fn area(size: SizeValue) -> u32 {
+    size.width * size.height
+}
+
+#[test]
+fn expose() {
+    let mut s = SizeValue {
+        width: 2,
+        height: 3,
+    }
+    .to_array_struct();
+
+    assert_eq!(area(s.val()), 6);
+    let refs: SizeRefs = s.refs();
+    assert_eq!(*refs.height, 3);
+    let muts: SizeMuts = s.muts();
+    *muts.width = 4;
+    assert_eq!(s[SizeIndex::width()], 4);
+}
+
+#[array_as_struct(keep_named = "PointNamed")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    x: i16,
+    y: i16,
+}
+
+#[test]
+fn keep_named() {
+    let named = PointNamed { x: 1, y: 2 };
+    let point = Point::from(named);
+
+    assert_eq!(point, Point([1, 2]));
+    assert_eq!(PointNamed::from(point), named);
+    assert_eq!(point.val().y, 2);
+}
+
+#[test]
+fn field_enum() {
+    let describe = |field| match field {
+        FooField::bar => "first",
+        FooField::baz => "second",
+    };
+    assert_eq!(describe(FooField::baz), "second");
+    assert_eq!(FooField::baz.index(), 1);
+    assert_eq!(FooField::baz as usize, 1);
+    assert_eq!(FooField::from_index(0), Some(FooField::bar));
+    assert_eq!(FooField::from_index(2), None);
+}
+
+#[array_as_struct(debug, display)]
+#[derive(Debug)]
+pub struct Named {
+    left: u8,
+    right: u8,
+}
+
+#[test]
+fn debug() {
+    assert_eq!(
+        format!("{:?}", Named([1, 2])),
+        "Named { left: 1, right: 2 }"
+    );
+}
+
+#[test]
+fn display() {
+    assert_eq!(format!("{}", Named([1, 2])), "left=1, right=2");
+    assert_eq!(format!("{:#}", Named([1, 2])), "left=1\nright=2");
+    assert_eq!(format!("{:>2}", Named([1, 2])), "left= 1, right= 2");
+}
+
+#[array_as_struct(hash, ord)]
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct Cell {
+    row: u16,
+    col: u16,
+}
+
+#[test]
+fn hash() {
+    use std::collections::HashMap;
+
+    let mut grid = HashMap::new();
+    grid.insert(Cell([1, 2]), 'x');
+
+    assert_eq!(grid.get(&Cell([1, 2])), Some(&'x'));
+    assert_eq!(grid.get(&[1, 2]), Some(&'x'));
+    assert_eq!(grid.get(&[2, 1]), None);
+}
+
+#[test]
+fn ord() {
+    let mut cells = vec![Cell([2, 0]), Cell([1, 5]), Cell([1, 2])];
+    cells.sort();
+
+    assert_eq!(cells, [Cell([1, 2]), Cell([1, 5]), Cell([2, 0])]);
+    assert!(Vec3f([0.0, f64::NAN, 0.0])
+        .partial_cmp(&Vec3f([0.0; 3]))
+        .is_none());
+}
+
+#[array_as_struct(ops)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pair<T>
+where
+    T: Copy,
+{
+    first: T,
+    second: T,
+}
+
+#[test]
+fn where_clause() {
+    // Workaround rust-lang/rust#86935
+    type Value<T> = <Pair<T> as ArrayStruct>::Value;
+
+    let mut p = Pair::from_val(Value {
+        first: 1u8,
+        second: 2,
+    });
+
+    assert_eq!(*p.refs().second, 2);
+    *p.muts().first = 3;
+    assert_eq!(p.val().first, 3);
+    assert_eq!(p + Pair([1, 1]), Pair([4, 3]));
+}
+
+#[array_as_struct]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rows<'a, T: Copy = u8, const N: usize = 2> {
+    top: &'a [T; N],
+    bottom: &'a [T; N],
+}
+
+#[test]
+fn const_generics() {
+    let (top, bottom) = ([1, 2], [3, 4]);
+    let rows: Rows = Rows([&top, &bottom]);
+    assert_eq!(rows.refs().bottom, &&[3, 4]);
+    assert_eq!(Rows::splat(&[5u16; 3]), Rows([&[5; 3], &[5; 3]]));
+    assert_eq!(<Rows as ArrayStruct>::FIELD_COUNT, 2);
+}
+
+#[array_as_struct(debug, ops, bitops)]
+pub struct Cursors<'a> {
+    read: &'a mut u32,
+    write: &'a mut u32,
+}
+
+#[test]
+fn lifetimes() {
+    let (mut read, mut write) = (1, 2);
+    let mut cursors = Cursors::new(&mut read, &mut write);
+    **cursors.muts().write += 10;
+    **cursors.write_mut() += 10;
+    for cursor in cursors.iter_mut() {
+        **cursor += 1;
+    }
+    assert_eq!(*cursors.refs().write, &23);
+    assert_eq!(format!("{cursors:?}"), "Cursors { read: 2, write: 23 }");
+
+    let value = cursors.val();
+    *value.read = 0;
+    assert_eq!((read, write), (0, 23));
+}
+
+#[array_as_struct(inline = "never", with)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coarse {
+    lo: u16,
+    hi: u16,
+}
+
+#[test]
+fn inline_never() {
+    let c = Coarse::new(1, 2).with_hi(3);
+
+    assert_eq!(c.hi_copied(), 3);
+    assert_eq!(c.sum::<u16>(), 4);
+}
+
+#[array_as_struct(ops, debug, display, hash, with, swizzle, serde)]
+#[derive(Clone, Copy, PartialEq)]
+pub struct Scalar {
+    value: f64,
+}
+
+#[test]
+fn single_field() {
+    let mut s = Scalar::new(-3.0);
+
+    assert_eq!(s.dot(&s), 9.0);
+    assert_eq!(s.length(), 3.0);
+    assert_eq!(s.normalize(), Scalar([-1.0]));
+    assert_eq!(Scalar([0.0]).normalize(), Scalar([0.0]));
+    assert!(s.iter().eq(&[-3.0]));
+    assert!(s.refs().into_iter().eq(&[-3.0]));
+    *s.muts().value += 1.0;
+    assert_eq!(s.into_tuple(), (-2.0,));
+    assert_eq!(s.swizzle::<0>(), s);
+    assert_eq!(s.rotated_left(1), s);
+    assert_eq!(format!("{s:?}"), "Scalar { value: -2.0 }");
+    assert_eq!(s * 2.0, Scalar([-4.0]));
+}
+
+#[array_as_struct(element = u8)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Nothing {}
+
+#[test]
+fn empty() {
+    let n = Nothing::default();
+    assert_eq!(n.to_array(), [0u8; 0]);
+    assert!(n.is_empty());
+    assert_eq!(<Nothing as ArrayStruct>::FIELD_COUNT, 0);
+}
+
+#[array_as_struct(align(16))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lanes {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+#[test]
+fn align() {
+    assert_eq!(core::mem::align_of::<Lanes>(), 16);
+    assert_eq!(core::mem::size_of::<Lanes>(), 16);
+
+    let l = Lanes::new(1.0, 2.0, 3.0);
+    assert_eq!(l.to_array(), [1.0, 2.0, 3.0]);
+    assert_eq!(l.z(), &3.0);
+}
+
+#[array_as_struct(keep_named = "BytePairNamed")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(transparent)]
+pub struct BytePair {
+    a: u8,
+    b: u8,
+}
+
+#[array_as_struct]
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(C)]
+pub struct Extent2 {
+    w: u16,
+    h: u16,
+}
+
+#[test]
+fn user_repr() {
+    assert_eq!(core::mem::size_of::<BytePair>(), 2);
+    assert_eq!(
+        BytePair::from(BytePairNamed { a: 1, b: 2 }),
+        BytePair([1, 2])
+    );
+    assert_eq!(BytePair([3, 4]).val().b, 4);
+
+    assert_eq!(core::mem::size_of::<Extent2>(), 4);
+    assert_eq!(Extent2::new(5, 6).val().h, 6);
+}
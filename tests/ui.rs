@@ -0,0 +1,79 @@
+//! Checks the diagnostics of misuses of the attribute against the `.stderr`
+//! snapshots in `tests/ui`, by compiling each case with `rustc` against the
+//! derive crate built alongside this test. Run with `UI_BLESS=1` to overwrite
+//! the snapshots.
+
+use std::{
+    env,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+/// Finds the most recently built derive crate next to this test binary
+fn derive_crate() -> PathBuf {
+    let exe = env::current_exe().expect("test binary path");
+    let deps = exe.parent().expect("test binary directory");
+    let prefix = format!("{}array_as_struct_derive-", env::consts::DLL_PREFIX);
+    fs::read_dir(deps)
+        .expect("readable deps directory")
+        .map(|entry| entry.expect("readable deps entry").path())
+        .filter(|path| {
+            let name = path.file_name().and_then(OsStr::to_str).unwrap_or_default();
+            name.starts_with(&prefix) && name.ends_with(env::consts::DLL_SUFFIX)
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .expect("the derive crate is built")
+}
+
+fn compile(root: &Path, derive: &Path, case: &Path) -> String {
+    let out_dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("ui");
+    let output = Command::new(env::var_os("RUSTC").unwrap_or_else(|| "rustc".into()))
+        .current_dir(root)
+        .args([
+            "--edition=2021",
+            "--crate-type=lib",
+            "--emit=metadata",
+            "--color=never",
+        ])
+        .arg("--out-dir")
+        .arg(&out_dir)
+        .arg("--extern")
+        .arg(format!("array_as_struct_derive={}", derive.display()))
+        .arg(case.strip_prefix(root).expect("case inside the crate"))
+        .output()
+        .expect("rustc runs");
+    assert!(!output.status.success(), "{} compiled", case.display());
+    String::from_utf8(output.stderr).expect("utf-8 diagnostics")
+}
+
+#[test]
+fn ui() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let derive = derive_crate();
+    let bless = env::var_os("UI_BLESS").is_some();
+
+    let mut cases = fs::read_dir(root.join("tests/ui"))
+        .expect("readable ui directory")
+        .map(|entry| entry.expect("readable ui entry").path())
+        .filter(|path| path.extension() == Some(OsStr::new("rs")))
+        .collect::<Vec<_>>();
+    cases.sort();
+
+    let mut mismatches = Vec::new();
+    for case in cases {
+        let actual = compile(root, &derive, &case);
+        let snapshot = case.with_extension("stderr");
+        if bless {
+            fs::write(&snapshot, &actual).expect("writable snapshot");
+        } else if fs::read_to_string(&snapshot).ok().as_deref() != Some(&*actual) {
+            mismatches.push(format!("{}:\n{}", snapshot.display(), actual));
+        }
+    }
+    assert!(
+        mismatches.is_empty(),
+        "mismatched diagnostics\n\n{}",
+        mismatches.join("\n")
+    );
+}
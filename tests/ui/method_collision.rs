@@ -0,0 +1,13 @@
+use array_as_struct_derive::array_as_struct;
+
+#[array_as_struct]
+pub struct Clash {
+    to_array: u8,
+    b: u8,
+}
+
+#[array_as_struct]
+pub struct ExtClash {
+    iter: u8,
+    sum: u8,
+}
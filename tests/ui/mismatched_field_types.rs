@@ -0,0 +1,9 @@
+use array_as_struct_derive::array_as_struct;
+
+#[array_as_struct]
+pub struct Mixed {
+    a: u8,
+    b: u16,
+    c: u8,
+    d: i8,
+}
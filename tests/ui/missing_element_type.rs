@@ -0,0 +1,4 @@
+use array_as_struct_derive::array_as_struct;
+
+#[array_as_struct]
+pub struct Empty {}
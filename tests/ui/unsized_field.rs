@@ -0,0 +1,17 @@
+use array_as_struct_derive::array_as_struct;
+
+#[array_as_struct]
+pub struct Unsized {
+    a: str,
+}
+
+#[array_as_struct]
+pub struct Slices {
+    a: [u8],
+    b: [u8],
+}
+
+#[array_as_struct]
+pub struct Objects {
+    a: dyn Send,
+}